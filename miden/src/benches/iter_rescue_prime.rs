@@ -5,9 +5,11 @@ use miden::{
 };
 use miden_core::chiplets;
 use rustbench::Benchmark;
+use tracing::info_span;
 
 pub struct Job {
     num_iter: u32,
+    prover: String,
     program: Program,
     program_info: ProgramInfo,
     program_inputs: StackInputs,
@@ -15,8 +17,10 @@ pub struct Job {
     program_outputs: StackOutputs,
 }
 
-pub fn new_jobs() -> Vec<<Job as Benchmark>::Spec> {
-    vec![10, 100, 1000]
+pub const DEFAULT_SIZES: &[u32] = &[10, 100, 1000];
+
+pub fn new_jobs(sizes: &[u32]) -> Vec<<Job as Benchmark>::Spec> {
+    sizes.to_vec()
 }
 
 impl Benchmark for Job {
@@ -24,6 +28,12 @@ impl Benchmark for Job {
     type Spec = u32;
     type ComputeOut = Vec<u64>;
     type ProofType = miden::ExecutionProof;
+    type Prover = super::Prover;
+    type Artifact = super::Artifact;
+
+    fn prover_name(&self) -> String {
+        self.prover.clone()
+    }
 
     fn job_size(spec: &Self::Spec) -> u32 {
         *spec
@@ -37,7 +47,20 @@ impl Benchmark for Job {
         proof.to_bytes().len() as u32
     }
 
-    fn new(num_iter: Self::Spec) -> Self {
+    fn public_output(output: &Self::ComputeOut, _proof: &Self::ProofType) -> Vec<u8> {
+        output.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8> {
+        proof.to_bytes()
+    }
+
+    fn security_bits(proof: &Self::ProofType) -> u32 {
+        proof.security_level()
+    }
+
+    fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self {
+        let num_iter = *spec;
         let source = format!(
             "  
             # stack start: [a3=0, a2=0, a1=0, a0=0, ...]
@@ -59,21 +82,23 @@ impl Benchmark for Job {
 
         // Compiling the program
         let assembler = Assembler::default();
-        let program = assembler
-            .compile(source.as_str())
-            .expect("Could not compile source");
+        let program = info_span!("assemble").in_scope(|| {
+            assembler
+                .compile(source.as_str())
+                .expect("Could not compile source")
+        });
 
         let program_hash = program.hash();
         let kernel = Kernel::default();
         let program_info = ProgramInfo::new(program_hash, kernel);
 
-        // default (96 bits of security)
-        let proof_options = ProofOptions::with_96_bit_security();
+        let proof_options = prover.proof_options.clone();
 
         let program_outputs = StackOutputs::new(vec![], vec![]);
 
         Job {
             num_iter,
+            prover: prover.name.clone(),
             program,
             program_info,
             program_inputs,
@@ -109,6 +134,25 @@ impl Benchmark for Job {
         (stack_output, proof)
     }
 
+    fn guest_execute(&mut self) -> Self::ComputeOut {
+        let program = &self.program;
+        let program_input = self.program_inputs.clone();
+
+        // Creating an empty advice provider
+        let advice_inputs = AdviceInputs::default()
+            .with_stack_values(vec![])
+            .map_err(|e| e.to_string());
+        let advice_provider = MemAdviceProvider::from(advice_inputs.unwrap());
+
+        let trace = miden::execute(program, program_input, advice_provider).expect("results");
+        let output = trace.stack_outputs();
+
+        let mut stack_output = output.stack_truncated(4).to_vec();
+        stack_output.reverse();
+
+        stack_output
+    }
+
     /// Compute on host CPU
     fn host_compute(&mut self) -> Option<Self::ComputeOut> {
         // We also hash a vector of four 0's
@@ -142,4 +186,17 @@ impl Benchmark for Job {
             }
         }
     }
+
+    fn artifact(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact {
+        super::Artifact::new(
+            &self.program_info,
+            &self.program_inputs,
+            &self.program_outputs,
+            proof,
+        )
+    }
+
+    fn verify_artifact(artifact: &Self::Artifact) -> bool {
+        artifact.verify()
+    }
 }
@@ -5,9 +5,11 @@ use miden::{
 use miden_stdlib::StdLibrary;
 use rustbench::Benchmark;
 use sha2::{Digest, Sha256};
+use tracing::info_span;
 
 pub struct Job {
     num_iter: u32,
+    prover: String,
     program: Program,
     program_info: ProgramInfo,
     program_inputs: StackInputs,
@@ -15,8 +17,10 @@ pub struct Job {
     program_outputs: StackOutputs,
 }
 
-pub fn new_jobs() -> Vec<<Job as Benchmark>::Spec> {
-    vec![10, 100]
+pub const DEFAULT_SIZES: &[u32] = &[10, 100];
+
+pub fn new_jobs(sizes: &[u32]) -> Vec<<Job as Benchmark>::Spec> {
+    sizes.to_vec()
 }
 
 impl Benchmark for Job {
@@ -24,6 +28,12 @@ impl Benchmark for Job {
     type Spec = u32;
     type ComputeOut = Vec<u64>;
     type ProofType = miden::ExecutionProof;
+    type Prover = super::Prover;
+    type Artifact = super::Artifact;
+
+    fn prover_name(&self) -> String {
+        self.prover.clone()
+    }
 
     fn job_size(spec: &Self::Spec) -> u32 {
         *spec
@@ -37,7 +47,20 @@ impl Benchmark for Job {
         proof.to_bytes().len() as u32
     }
 
-    fn new(num_iter: Self::Spec) -> Self {
+    fn public_output(output: &Self::ComputeOut, _proof: &Self::ProofType) -> Vec<u8> {
+        output.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8> {
+        proof.to_bytes()
+    }
+
+    fn security_bits(proof: &Self::ProofType) -> u32 {
+        proof.security_level()
+    }
+
+    fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self {
+        let num_iter = *spec;
         let source = format!(
             "  
             use.std::crypto::hashes::sha256
@@ -62,20 +85,22 @@ impl Benchmark for Job {
             .with_library(&StdLibrary::default())
             .expect("failed to load stdlib");
 
-        let program = assembler
-            .compile(source.as_str())
-            .expect("Could not compile source");
+        let program = info_span!("assemble").in_scope(|| {
+            assembler
+                .compile(source.as_str())
+                .expect("Could not compile source")
+        });
         let program_hash = program.hash();
         let kernel = Kernel::default();
         let program_info = ProgramInfo::new(program_hash, kernel);
 
-        // default (96 bits of security)
-        let proof_options = ProofOptions::with_96_bit_security();
+        let proof_options = prover.proof_options.clone();
 
         let program_outputs = StackOutputs::new(vec![], vec![]);
 
         Job {
             num_iter,
+            prover: prover.name.clone(),
             program,
             program_info,
             program_inputs,
@@ -110,6 +135,20 @@ impl Benchmark for Job {
         (stack_output, proof)
     }
 
+    fn guest_execute(&mut self) -> Self::ComputeOut {
+        let program = self.program.clone();
+        let program_inputs = self.program_inputs.clone();
+
+        // Creating an empty advice provider
+        let advice_inputs = AdviceInputs::default()
+            .with_stack_values(vec![])
+            .map_err(|e| e.to_string());
+        let advice_provider = MemAdviceProvider::from(advice_inputs.unwrap());
+
+        let trace = miden::execute(&program, program_inputs, advice_provider).expect("results");
+        trace.stack_outputs().stack_truncated(8).to_vec()
+    }
+
     fn host_compute(&mut self) -> Option<<Self as Benchmark>::ComputeOut> {
         let mut data = Vec::from([0u8; 32]);
 
@@ -147,4 +186,17 @@ impl Benchmark for Job {
             }
         }
     }
+
+    fn artifact(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact {
+        super::Artifact::new(
+            &self.program_info,
+            &self.program_inputs,
+            &self.program_outputs,
+            proof,
+        )
+    }
+
+    fn verify_artifact(artifact: &Self::Artifact) -> bool {
+        artifact.verify()
+    }
 }
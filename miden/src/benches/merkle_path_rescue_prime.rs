@@ -6,15 +6,16 @@ use miden::{
 };
 use miden_core::StarkField;
 use rustbench::Benchmark;
+use tracing::info_span;
 
 /// Create a Merkle path of depth 32 and then  
 /// the job_size is the number of Merkle paths we verify.
 /// So, for job_size=10 we verify 10 Merkle paths of depth 32.
 /// Unfortunately in Miden v0.5 I can only create a Sparse Merkle Tree of depth 64.
 /// ToDo: Update benchmark when we release Miden v0.6
-
 pub struct Job {
     num_iter: u32,
+    prover: String,
     program: Program,
     program_info: ProgramInfo,
     program_inputs: StackInputs,
@@ -24,8 +25,10 @@ pub struct Job {
     root_as_u64: Vec<u64>,
 }
 
-pub fn new_jobs() -> Vec<<Job as Benchmark>::Spec> {
-    vec![10, 100, 1000]
+pub const DEFAULT_SIZES: &[u32] = &[10, 100, 1000];
+
+pub fn new_jobs(sizes: &[u32]) -> Vec<<Job as Benchmark>::Spec> {
+    sizes.to_vec()
 }
 
 impl Benchmark for Job {
@@ -33,6 +36,12 @@ impl Benchmark for Job {
     type Spec = u32;
     type ComputeOut = Vec<u64>;
     type ProofType = miden::ExecutionProof;
+    type Prover = super::Prover;
+    type Artifact = super::Artifact;
+
+    fn prover_name(&self) -> String {
+        self.prover.clone()
+    }
 
     fn job_size(spec: &Self::Spec) -> u32 {
         *spec
@@ -46,7 +55,20 @@ impl Benchmark for Job {
         proof.to_bytes().len() as u32
     }
 
-    fn new(num_iter: Self::Spec) -> Self {
+    fn public_output(output: &Self::ComputeOut, _proof: &Self::ProofType) -> Vec<u8> {
+        output.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8> {
+        proof.to_bytes()
+    }
+
+    fn security_bits(proof: &Self::ProofType) -> u32 {
+        proof.security_level()
+    }
+
+    fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self {
+        let num_iter = *spec;
         let source = format!(
             "  
             begin
@@ -96,15 +118,16 @@ impl Benchmark for Job {
 
         // Compiling the program
         let assembler = Assembler::default();
-        let program = assembler
-            .compile(source.as_str())
-            .expect("Could not compile source");
+        let program = info_span!("assemble").in_scope(|| {
+            assembler
+                .compile(source.as_str())
+                .expect("Could not compile source")
+        });
         let program_hash = program.hash();
         let kernel = Kernel::default();
         let program_info = ProgramInfo::new(program_hash, kernel);
 
-        // Default (96 bits of security)
-        let proof_options = ProofOptions::with_96_bit_security();
+        let proof_options = prover.proof_options.clone();
 
         let program_outputs = StackOutputs::new(vec![], vec![]);
 
@@ -114,15 +137,10 @@ impl Benchmark for Job {
         let merkle_leafs_keys: Vec<u64> = (0..1000).collect();
         let mut merkle_leafs: Vec<(u64, Word)> = Vec::new();
 
-        for i in 0..merkle_leafs_keys.len() {
+        for (i, key) in merkle_leafs_keys.iter().enumerate() {
             merkle_leafs.push((
                 i as u64,
-                [
-                    Felt::new(merkle_leafs_keys[i]),
-                    Felt::ZERO,
-                    Felt::ZERO,
-                    Felt::ZERO,
-                ],
+                [Felt::new(*key), Felt::ZERO, Felt::ZERO, Felt::ZERO],
             ));
         }
 
@@ -165,6 +183,7 @@ impl Benchmark for Job {
 
         Job {
             num_iter,
+            prover: prover.name.clone(),
             program,
             program_info,
             program_inputs,
@@ -207,6 +226,20 @@ impl Benchmark for Job {
         (stack_output, proof)
     }
 
+    fn guest_execute(&mut self) -> Self::ComputeOut {
+        let program = self.program.clone();
+        let program_inputs = self.program_inputs.clone();
+        let advice_provider = self.advice_provider.clone();
+
+        let trace = miden::execute(&program, program_inputs, advice_provider).expect("results");
+        let output = trace.stack_outputs();
+
+        let mut stack_output = output.stack_truncated(4).to_vec();
+        stack_output.reverse();
+
+        stack_output
+    }
+
     fn verify_proof(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> bool {
         let program_info = self.program_info.clone();
         let program_inputs = self.program_inputs.clone();
@@ -222,4 +255,17 @@ impl Benchmark for Job {
             Err(_err) => false,
         }
     }
+
+    fn artifact(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact {
+        super::Artifact::new(
+            &self.program_info,
+            &self.program_inputs,
+            &self.program_outputs,
+            proof,
+        )
+    }
+
+    fn verify_artifact(artifact: &Self::Artifact) -> bool {
+        artifact.verify()
+    }
 }
@@ -2,3 +2,92 @@ pub mod iter_blake3;
 pub mod iter_rescue_prime;
 pub mod iter_sha2;
 pub mod merkle_path_rescue_prime;
+
+use miden::math::StarkField;
+use miden::utils::{Deserializable, Serializable};
+use miden::{ExecutionProof, ProgramInfo, ProofOptions, StackInputs, StackOutputs};
+use serde::{Deserialize, Serialize};
+
+/// Every miden job proves with the same options, chosen from the requested
+/// security level. Levels above the default are part of the prover name, so
+/// that results, traces and profiles of a security sweep stay apart.
+#[derive(Clone)]
+pub struct Prover {
+    pub name: String,
+    pub proof_options: ProofOptions,
+}
+
+impl Prover {
+    pub fn new(security: Option<u32>) -> Self {
+        let (name, proof_options) = match security {
+            // Default
+            None | Some(96) => ("miden", ProofOptions::with_96_bit_security()),
+            Some(128) => ("miden-128", ProofOptions::with_128_bit_security()),
+            Some(bits) => panic!("Miden supports 96 or 128 bits of security, not {}", bits),
+        };
+        Prover {
+            name: String::from(name),
+            proof_options,
+        }
+    }
+}
+
+impl std::fmt::Display for Prover {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// A proof with its program and public inputs and outputs, as saved with
+/// `--save-proofs`. The stacks are kept as plain values since miden cannot
+/// deserialize `StackInputs` and `StackOutputs`.
+#[derive(Serialize, Deserialize)]
+pub struct Artifact {
+    program_info: Vec<u8>,
+    stack_inputs: Vec<u64>,
+    stack_outputs: Vec<u64>,
+    overflow_addrs: Vec<u64>,
+    proof: Vec<u8>,
+}
+
+impl Artifact {
+    pub fn new(
+        program_info: &ProgramInfo,
+        stack_inputs: &StackInputs,
+        stack_outputs: &StackOutputs,
+        proof: &ExecutionProof,
+    ) -> Self {
+        Artifact {
+            program_info: program_info.to_bytes(),
+            // `StackInputs::new` reverses its values back into a stack
+            stack_inputs: stack_inputs
+                .values()
+                .iter()
+                .rev()
+                .map(|v| v.as_int())
+                .collect(),
+            stack_outputs: stack_outputs.stack().to_vec(),
+            overflow_addrs: stack_outputs.overflow_addrs().to_vec(),
+            proof: proof.to_bytes(),
+        }
+    }
+
+    pub fn verify(&self) -> bool {
+        let program_info = ProgramInfo::read_from_bytes(&self.program_info).unwrap();
+        let stack_inputs = StackInputs::try_from_values(self.stack_inputs.iter().copied()).unwrap();
+        let stack_outputs =
+            StackOutputs::new(self.stack_outputs.clone(), self.overflow_addrs.clone());
+        let proof = ExecutionProof::from_bytes(&self.proof).unwrap();
+
+        let result = miden::verify(program_info, stack_inputs, stack_outputs, proof)
+            .map_err(|err| format!("Program failed verification! - {}", err));
+
+        match result {
+            Ok(_) => true,
+            Err(err) => {
+                println!("{}", err);
+                false
+            }
+        }
+    }
+}
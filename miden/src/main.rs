@@ -1,5 +1,6 @@
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::path::{Path, PathBuf};
 
 mod benches;
 
@@ -7,14 +8,22 @@ use benches::iter_blake3;
 use benches::iter_rescue_prime;
 use benches::iter_sha2;
 use benches::merkle_path_rescue_prime;
-use rustbench::{init_logging, run_jobs};
+use rustbench::{init_logging, run_jobs, verify_jobs, Benchmark, RunOptions, Suite};
+
+// Counts heap allocations so that every job reports per-phase heap usage
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOC: rustbench::CountingAlloc = rustbench::CountingAlloc;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    // CSV output file
+    // CSV output file; needed by every command but `list`
     #[arg(long, value_name = "FILE")]
-    out: PathBuf,
+    out: Option<PathBuf>,
+
+    #[command(flatten)]
+    run: RunOptions,
 
     #[command(subcommand)]
     command: Command,
@@ -27,31 +36,128 @@ enum Command {
     IterSha2,
     IterRescuePrime,
     MerklePathRescuePrime,
+    /// Verify the proofs saved with --save-proofs in DIR, without proving
+    Verify {
+        dir: PathBuf,
+    },
+    /// Print the name of every bench, one per line
+    List,
+}
+
+impl Cli {
+    fn out(&self) -> &Path {
+        self.out.as_deref().unwrap_or_else(|| {
+            Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "--out <FILE> is required",
+                )
+                .exit()
+        })
+    }
+}
+
+// Every subcommand but these runs a bench of the same name
+fn bench_names() -> Vec<String> {
+    Cli::command()
+        .get_subcommands()
+        .map(|c| c.get_name().replace('-', "_"))
+        .filter(|name| !["all", "verify", "list"].contains(&name.as_str()))
+        .collect()
+}
+
+const SYSTEM: &str = "miden";
+
+// Runs one bench with the sizes and options given on the command line or in
+// the suite; benches left out of the suite are skipped.
+fn run_bench<B: Benchmark<Spec = u32, Prover = benches::Prover>>(
+    cli: &Cli,
+    suite: Option<&Suite>,
+    bench: &str,
+    default_sizes: &[u32],
+    new_jobs: fn(&[u32]) -> Vec<u32>,
+    provers: &Vec<benches::Prover>,
+) {
+    match cli.run.for_bench(suite, SYSTEM, bench) {
+        Some(options) => {
+            let specs = new_jobs(&options.sizes_or(default_sizes));
+            run_jobs::<B>(cli.out(), &specs, provers, &options);
+        }
+        None => println!("Skipping {}: not in the suite", bench),
+    }
 }
 
 fn main() {
     init_logging();
     let cli = Cli::parse();
 
-    let prover = String::from("miden");
+    if cli.command == Command::List {
+        for bench in bench_names() {
+            println!("{}", bench);
+        }
+        return;
+    }
+    // Every other command writes to --out, so check for it before any work
+    cli.out();
+
+    if let Command::Verify { dir } = &cli.command {
+        verify_jobs::<iter_blake3::Job>(cli.out(), dir, &cli.run);
+        verify_jobs::<iter_sha2::Job>(cli.out(), dir, &cli.run);
+        verify_jobs::<iter_rescue_prime::Job>(cli.out(), dir, &cli.run);
+        verify_jobs::<merkle_path_rescue_prime::Job>(cli.out(), dir, &cli.run);
+        return;
+    }
+
+    let suite = cli.run.load_suite();
+    let suite = suite.as_ref();
+    let provers: Vec<benches::Prover> = cli
+        .run
+        .security_levels(suite, SYSTEM)
+        .into_iter()
+        .map(benches::Prover::new)
+        .collect();
 
     if cli.command == Command::All || cli.command == Command::IterBlake3 {
-        run_jobs::<iter_blake3::Job>(&prover, &cli.out, iter_blake3::new_jobs());
+        run_bench::<iter_blake3::Job>(
+            &cli,
+            suite,
+            "iter_blake3",
+            iter_blake3::DEFAULT_SIZES,
+            iter_blake3::new_jobs,
+            &provers,
+        );
     }
 
     if cli.command == Command::All || cli.command == Command::IterSha2 {
-        run_jobs::<iter_sha2::Job>(&prover, &cli.out, iter_sha2::new_jobs());
+        run_bench::<iter_sha2::Job>(
+            &cli,
+            suite,
+            "iter_sha2",
+            iter_sha2::DEFAULT_SIZES,
+            iter_sha2::new_jobs,
+            &provers,
+        );
     }
 
     if cli.command == Command::All || cli.command == Command::IterRescuePrime {
-        run_jobs::<iter_rescue_prime::Job>(&prover, &cli.out, iter_rescue_prime::new_jobs());
+        run_bench::<iter_rescue_prime::Job>(
+            &cli,
+            suite,
+            "iter_rescue_prime",
+            iter_rescue_prime::DEFAULT_SIZES,
+            iter_rescue_prime::new_jobs,
+            &provers,
+        );
     }
 
     if cli.command == Command::All || cli.command == Command::MerklePathRescuePrime {
-        run_jobs::<merkle_path_rescue_prime::Job>(
-            &prover,
-            &cli.out,
-            merkle_path_rescue_prime::new_jobs(),
+        run_bench::<merkle_path_rescue_prime::Job>(
+            &cli,
+            suite,
+            "merkle_path_rescue_prime",
+            merkle_path_rescue_prime::DEFAULT_SIZES,
+            merkle_path_rescue_prime::new_jobs,
+            &provers,
         );
     }
 }
@@ -0,0 +1,15 @@
+use std::process::Command;
+
+// Executes every bench at a few small sizes without proving, and fails
+// unless each output matches the host's. Catches broken MASM in seconds
+// rather than the minutes a proof takes.
+#[test]
+fn every_bench_matches_host() {
+    let status = Command::new(env!("CARGO_BIN_EXE_miden-benchmark"))
+        .arg("--out")
+        .arg(std::env::temp_dir().join("miden-execute-check.csv"))
+        .args(["--sizes", "1,10", "--check", "execute", "all"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,420 @@
+use clap::Parser;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::Instant;
+
+mod manifest;
+mod systems;
+
+use manifest::{BenchRun, Environment, Manifest, Step, SystemRun};
+use rustbench::Suite;
+use systems::{System, SYSTEMS};
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    // Repository root holding one directory per system
+    #[arg(long, value_name = "DIR", default_value = concat!(env!("CARGO_MANIFEST_DIR"), "/.."))]
+    root: PathBuf,
+
+    // Run directory; defaults to data/<timestamp> under the root
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+
+    // Systems to run; all of them when omitted
+    #[arg(long = "system", value_name = "NAME")]
+    systems: Vec<String>,
+
+    // Benches to run; every bench of each selected system when omitted
+    #[arg(long = "bench", value_name = "NAME")]
+    benches: Vec<String>,
+
+    // Security level passed to every system; their defaults when omitted
+    #[arg(long, value_name = "BITS")]
+    security: Option<u32>,
+
+    // Suite file passed to every system, selecting benches, sizes and options
+    #[arg(long, value_name = "FILE")]
+    suite: Option<PathBuf>,
+
+    // Machine profile every system emulates, built in or defined in the suite
+    #[arg(long, value_name = "NAME")]
+    machine: Option<String>,
+
+    // Save every proof to proofs/ in the run directory, for the hosts' verify command
+    #[arg(long)]
+    save_proofs: bool,
+}
+
+// Runs `cmd`, copying its stdout and stderr both to the console and to `log`.
+fn run_logged(mut cmd: Command, log: &Path) -> Step {
+    let start = Instant::now();
+    let log_file = Mutex::new(File::create(log).expect("Could not create log file"));
+
+    let status = match cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
+        Ok(mut child) => {
+            let stdout = child.stdout.take().unwrap();
+            let stderr = child.stderr.take().unwrap();
+            std::thread::scope(|s| {
+                s.spawn(|| tee(stdout, std::io::stdout(), &log_file));
+                s.spawn(|| tee(stderr, std::io::stderr(), &log_file));
+            });
+            child.wait().ok()
+        }
+        Err(err) => {
+            let msg = format!("Could not start {:?}: {}\n", cmd, err);
+            eprint!("{}", msg);
+            let _ = log_file.lock().unwrap().write_all(msg.as_bytes());
+            None
+        }
+    };
+
+    Step {
+        success: status.is_some_and(|s| s.success()),
+        exit_code: status.and_then(|s| s.code()),
+        duration_secs: start.elapsed().as_secs_f64(),
+        log: log.to_path_buf(),
+    }
+}
+
+fn tee(mut from: impl Read, mut console: impl Write, log: &Mutex<File>) {
+    let mut buf = [0u8; 8192];
+    loop {
+        match from.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let _ = console.write_all(&buf[..n]);
+                let _ = log.lock().unwrap().write_all(&buf[..n]);
+            }
+        }
+    }
+}
+
+// Each system pins its own toolchain with a rust-toolchain file. When the
+// orchestrator itself runs under `cargo run`, rustup exports the outer
+// toolchain to child processes, which would override those files.
+fn cargo_command(dir: &Path) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.current_dir(dir)
+        .env_remove("RUSTUP_TOOLCHAIN")
+        .env_remove("CARGO")
+        .env_remove("CARGO_TARGET_DIR");
+    cmd
+}
+
+// Version of the toolchain a system is pinned to
+fn rustc_version(dir: &Path) -> Option<String> {
+    let out = Command::new("rustc")
+        .current_dir(dir)
+        .env_remove("RUSTUP_TOOLCHAIN")
+        .arg("--version")
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+fn run_system(cli: &Cli, suite: Option<&Suite>, run_dir: &Path, system: &System) -> SystemRun {
+    let dir = cli.root.join(system.dir);
+
+    println!("--------------------------------------------------");
+    println!("Start: {}", system.team);
+    println!("--------------------------------------------------");
+
+    let mut build = cargo_command(&dir);
+    build.args(["build", "--release"]);
+    let build = run_logged(build, &run_dir.join(format!("{}-build.log", system.name)));
+
+    let mut benches = Vec::new();
+    let mut list_error = None;
+
+    if build.success {
+        let bin = dir.join("target").join("release").join(system.bin);
+        let output = run_dir.join(format!("{}.csv", system.name));
+        let names = systems::list_benches(&bin).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            list_error = Some(err);
+            Vec::new()
+        });
+
+        for bench in names {
+            if !cli.benches.is_empty() && !cli.benches.contains(&bench) {
+                continue;
+            }
+            // The host would skip it too, but then record nothing about it
+            if suite.is_some_and(|s| !s.selects(system.name, &bench)) {
+                println!("Skipping {}: not in the suite", bench);
+                benches.push(BenchRun {
+                    bench,
+                    skipped: true,
+                    step: None,
+                    output: output.clone(),
+                });
+                continue;
+            }
+
+            let mut cmd = Command::new(&bin);
+            cmd.current_dir(&dir).arg("--out").arg(&output);
+            if let Some(bits) = cli.security {
+                cmd.arg("--security").arg(bits.to_string());
+            }
+            if let Some(suite) = &cli.suite {
+                cmd.arg("--suite").arg(suite);
+            }
+            if let Some(machine) = &cli.machine {
+                cmd.arg("--machine").arg(machine);
+            }
+            if cli.save_proofs {
+                cmd.arg("--save-proofs");
+            }
+            cmd.arg(systems::subcommand(&bench));
+            if std::env::var_os("RUST_LOG").is_none() {
+                cmd.env("RUST_LOG", "info");
+            }
+
+            let log = run_dir.join(format!("{}-{}.log", system.name, bench));
+            let step = run_logged(cmd, &log);
+
+            benches.push(BenchRun {
+                bench,
+                skipped: false,
+                step: Some(step),
+                output: output.clone(),
+            });
+        }
+    }
+
+    println!("--------------------------------------------------");
+    println!("Done: {}", system.team);
+    println!("--------------------------------------------------");
+    println!();
+
+    SystemRun {
+        name: String::from(system.name),
+        rustc: rustc_version(&dir),
+        build,
+        list_error,
+        benches,
+    }
+}
+
+// Concatenates the per-system CSV files, taking the union of their columns.
+fn merge_csv(inputs: &[PathBuf], out_path: &Path) -> csv::Result<()> {
+    let mut headers: Vec<String> = Vec::new();
+    let mut rows = Vec::new();
+
+    for path in inputs {
+        let mut rdr = csv::Reader::from_path(path)?;
+        let hdr: Vec<String> = rdr.headers()?.iter().map(String::from).collect();
+        for h in &hdr {
+            if !headers.contains(h) {
+                headers.push(h.clone());
+            }
+        }
+        for record in rdr.records() {
+            let record = record?;
+            rows.push(
+                hdr.iter()
+                    .cloned()
+                    .zip(record.iter().map(String::from))
+                    .collect::<Vec<_>>(),
+            );
+        }
+    }
+
+    let mut out = csv::Writer::from_path(out_path)?;
+    out.write_record(&headers)?;
+    for row in rows {
+        out.write_record(headers.iter().map(|h| {
+            row.iter()
+                .find(|(k, _)| k == h)
+                .map(|(_, v)| v.as_str())
+                .unwrap_or("")
+        }))?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+// Concatenates the per-system JSON lines files written next to each CSV.
+fn merge_json(inputs: &[PathBuf], out_path: &Path) -> std::io::Result<()> {
+    let mut out = File::create(out_path)?;
+    for path in inputs {
+        let json = path.with_extension("jsonl");
+        if json.exists() {
+            out.write_all(&fs::read(json)?)?;
+        }
+    }
+    Ok(())
+}
+
+fn git_commit(root: &Path) -> Option<String> {
+    let out = Command::new("git")
+        .current_dir(root)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+fn main() {
+    let mut cli = Cli::parse();
+
+    // Hosts run from their own directories, so resolve the suite up front
+    if let Some(suite) = &cli.suite {
+        cli.suite = Some(
+            suite
+                .canonicalize()
+                .unwrap_or_else(|e| panic!("Could not find suite {}: {}", suite.display(), e)),
+        );
+    }
+
+    for name in &cli.systems {
+        if systems::find(name).is_none() {
+            panic!("Unknown system {:?}", name);
+        }
+    }
+    let selected: Vec<&System> = SYSTEMS
+        .iter()
+        .filter(|s| cli.systems.is_empty() || cli.systems.iter().any(|n| n == s.name))
+        .collect();
+    let suite = cli.suite.as_deref().map(Suite::load);
+
+    let timestamp = chrono::Utc::now().format("%Y-%m-%d_%H%M%S").to_string();
+    let run_dir = cli
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| cli.root.join("data").join(&timestamp));
+    fs::create_dir_all(&run_dir).expect("Could not create run directory");
+    let run_dir = run_dir.canonicalize().unwrap();
+
+    println!("Time stamp: {}", timestamp);
+    println!("Run directory: {}", run_dir.display());
+
+    let systems: Vec<SystemRun> = selected
+        .iter()
+        .map(|s| run_system(&cli, suite.as_ref(), &run_dir, s))
+        .collect();
+
+    // Benches are only known once the hosts are built, so misspelt ones can
+    // only be reported now, and only if every host could be listed
+    let listed = systems
+        .iter()
+        .all(|s| s.build.success && s.list_error.is_none());
+    let unknown: Vec<&str> = (cli.benches.iter())
+        .filter(|b| {
+            !systems
+                .iter()
+                .any(|s| s.benches.iter().any(|r| &r.bench == *b))
+        })
+        .map(String::as_str)
+        .collect();
+
+    let mut outputs: Vec<PathBuf> = systems
+        .iter()
+        .flat_map(|s| s.benches.iter().map(|b| b.output.clone()))
+        .filter(|p| p.exists())
+        .collect();
+    outputs.dedup();
+
+    let (results, results_json) = if outputs.is_empty() {
+        (None, None)
+    } else {
+        let path = run_dir.join("results.csv");
+        merge_csv(&outputs, &path).expect("Could not merge results");
+        let json_path = run_dir.join("results.jsonl");
+        merge_json(&outputs, &json_path).expect("Could not merge JSON results");
+        (Some(path), Some(json_path))
+    };
+
+    let manifest = Manifest {
+        timestamp,
+        git_commit: git_commit(&cli.root),
+        security: cli.security,
+        suite: cli.suite.clone(),
+        machine: cli.machine.clone(),
+        environment: Environment::detect(),
+        systems,
+        results,
+        results_json,
+    };
+
+    let file = File::create(run_dir.join("manifest.json")).expect("Could not create manifest");
+    serde_json::to_writer_pretty(file, &manifest).expect("Could not write manifest");
+
+    for system in &manifest.systems {
+        let status = |ok: bool| if ok { "ok" } else { "FAILED" };
+        println!(
+            "{:<10} build {:<6} {:>8.1}s",
+            system.name,
+            status(system.build.success),
+            system.build.duration_secs
+        );
+        for bench in &system.benches {
+            match &bench.step {
+                Some(step) => println!(
+                    "{:<10} {:<24} {:<6} {:>8.1}s",
+                    "",
+                    bench.bench,
+                    status(step.success),
+                    step.duration_secs
+                ),
+                None => println!("{:<10} {:<24} skipped", "", bench.bench),
+            }
+        }
+    }
+
+    if listed && !unknown.is_empty() {
+        eprintln!(
+            "No selected system has a bench named {}",
+            unknown.join(", ")
+        );
+        std::process::exit(1);
+    }
+    let failures = manifest.failures();
+    if failures > 0 {
+        eprintln!("{} step(s) failed; see {}", failures, run_dir.display());
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_the_union_of_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let miden = dir.path().join("miden.csv");
+        let risczero = dir.path().join("risczero.csv");
+        fs::write(&miden, "prover,job_size,security_bits\nmiden,10,96\n").unwrap();
+        fs::write(
+            &risczero,
+            "prover,machine,job_size\nrisczero,e2-standard-4,20\n",
+        )
+        .unwrap();
+        let out = dir.path().join("all.csv");
+
+        merge_csv(&[miden, risczero], &out).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&out).unwrap(),
+            "prover,job_size,security_bits,machine\n\
+             miden,10,96,\n\
+             risczero,20,,e2-standard-4\n"
+        );
+    }
+
+    #[test]
+    fn fails_to_merge_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.csv");
+        assert!(merge_csv(&[missing], &dir.path().join("all.csv")).is_err());
+    }
+}
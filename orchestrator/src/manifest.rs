@@ -0,0 +1,111 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Written to `manifest.json` in the run directory once every system has
+/// been built and run.
+#[derive(Serialize)]
+pub struct Manifest {
+    pub timestamp: String,
+    pub git_commit: Option<String>,
+    pub security: Option<u32>,
+    pub suite: Option<PathBuf>,
+    pub machine: Option<String>,
+    pub environment: Environment,
+    pub systems: Vec<SystemRun>,
+    pub results: Option<PathBuf>,
+    pub results_json: Option<PathBuf>,
+}
+
+/// The host the run was made on, so that results of different hosts can be
+/// told apart. Whatever cannot be found out is left empty.
+#[derive(Serialize)]
+pub struct Environment {
+    pub os: String,
+    pub arch: String,
+    pub kernel: Option<String>,
+    pub cpu: Option<String>,
+    pub logical_cpus: Option<usize>,
+    pub memory_gb: Option<f64>,
+}
+
+// Output of a command, if it ran successfully
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+// Value of the first `key: value` line of a /proc file
+fn proc_field(path: &str, key: &str) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    text.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key).then(|| v.trim().to_string())
+    })
+}
+
+impl Environment {
+    pub fn detect() -> Self {
+        let cpu = proc_field("/proc/cpuinfo", "model name")
+            .or_else(|| output("sysctl", &["-n", "machdep.cpu.brand_string"]));
+        let memory_bytes = proc_field("/proc/meminfo", "MemTotal")
+            .and_then(|kb| kb.trim_end_matches("kB").trim().parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+            .or_else(|| output("sysctl", &["-n", "hw.memsize"])?.parse().ok());
+        Environment {
+            os: String::from(std::env::consts::OS),
+            arch: String::from(std::env::consts::ARCH),
+            kernel: output("uname", &["-r"]),
+            cpu,
+            logical_cpus: std::thread::available_parallelism().ok().map(|n| n.get()),
+            memory_gb: memory_bytes.map(|b| (b as f64 / (1u64 << 30) as f64 * 10.0).round() / 10.0),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SystemRun {
+    pub name: String,
+    /// `rustc --version` of the system's pinned toolchain
+    pub rustc: Option<String>,
+    pub build: Step,
+    /// Why the benches of the built host could not be listed
+    pub list_error: Option<String>,
+    pub benches: Vec<BenchRun>,
+}
+
+#[derive(Serialize)]
+pub struct BenchRun {
+    pub bench: String,
+    /// Left out by the suite, so never run
+    pub skipped: bool,
+    /// How the bench ran, unless skipped
+    #[serde(flatten)]
+    pub step: Option<Step>,
+    pub output: PathBuf,
+}
+
+#[derive(Serialize)]
+pub struct Step {
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub duration_secs: f64,
+    pub log: PathBuf,
+}
+
+impl Manifest {
+    pub fn failures(&self) -> usize {
+        self.systems
+            .iter()
+            .map(|s| {
+                let build = usize::from(!s.build.success || s.list_error.is_some());
+                let benches = (s.benches.iter())
+                    .filter(|b| b.step.as_ref().is_some_and(|step| !step.success))
+                    .count();
+                build + benches
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,92 @@
+// The ZK systems under benchmark. Each one lives in its own cargo workspace,
+// pinned to its own toolchain, and exposes a host binary taking
+// `--out FILE <bench>`. Its benches are whatever `list` prints.
+
+use std::path::Path;
+use std::process::Command;
+
+pub struct System {
+    pub name: &'static str,
+    pub team: &'static str,
+    pub dir: &'static str,
+    pub bin: &'static str,
+}
+
+/// The benches of a built host binary, as printed by its `list` command.
+pub fn list_benches(bin: &Path) -> Result<Vec<String>, String> {
+    let out = Command::new(bin)
+        .arg("list")
+        .output()
+        .map_err(|e| format!("Could not start {}: {}", bin.display(), e))?;
+    if !out.status.success() {
+        return Err(format!(
+            "{} list failed: {}",
+            bin.display(),
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(parse_bench_list(&String::from_utf8_lossy(&out.stdout)))
+}
+
+// One bench per line, ignoring surrounding whitespace and blank lines.
+fn parse_bench_list(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+pub const SYSTEMS: [System; 2] = [
+    System {
+        name: "miden",
+        team: "Polygon Miden",
+        dir: "miden",
+        bin: "miden-benchmark",
+    },
+    System {
+        name: "risczero",
+        team: "RISC Zero",
+        dir: "risczero",
+        bin: "host",
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static System> {
+    SYSTEMS.iter().find(|s| s.name == name)
+}
+
+/// Host subcommands are the kebab-case form of the bench name.
+pub fn subcommand(bench: &str) -> String {
+    bench.replace('_', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_one_bench_per_line() {
+        let benches = parse_bench_list("iter_sha2\r\n  fact \n\nbig_sha2\n");
+        assert_eq!(benches, ["iter_sha2", "fact", "big_sha2"]);
+        assert!(parse_bench_list("").is_empty());
+    }
+
+    #[test]
+    fn reports_hosts_that_cannot_start() {
+        let err = list_benches(Path::new("/nonexistent/host")).unwrap_err();
+        assert!(
+            err.starts_with("Could not start /nonexistent/host"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn names_subcommands_in_kebab_case() {
+        assert_eq!(
+            subcommand("merkle_path_rescue_prime"),
+            "merkle-path-rescue-prime"
+        );
+    }
+}
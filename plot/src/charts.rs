@@ -0,0 +1,1211 @@
+// Charts of every metric against job size, one per metric and job, with a
+// series for every prover found in the results; or of the trade-off between
+// prove time and the other metrics, one per metric, job and job size.
+
+use crate::prices::Prices;
+use crate::results::{MemoryRow, Row};
+use crate::tables;
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::combinators::LogCoord;
+use plotters::coord::ranged1d::{Ranged, ValueFormatter};
+use plotters::coord::types::RangedCoordf32;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImageFormat {
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Kind {
+    /// The median of every job size, joined by a line per prover
+    Line,
+    /// A box per job size and prover: quartiles, whiskers to the furthest
+    /// sample within 1.5 IQR, and the samples beyond as dots
+    Box,
+    /// A group of bars per job size, one bar per prover, up to the median
+    Bar,
+    /// A point per prover configuration at its median prove time and median
+    /// of another metric, for every job size, with the Pareto frontier
+    Scatter,
+}
+
+impl Kind {
+    // Whether job sizes are evenly spaced categories rather than numbers
+    fn categorical(&self) -> bool {
+        matches!(self, Kind::Box | Kind::Bar)
+    }
+}
+
+/// How line charts show the samples around each median.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Spread {
+    None,
+    /// An error bar from the smallest to the largest sample
+    Bars,
+    /// A shaded band between the smallest and the largest samples
+    Band,
+}
+
+/// What line, box and bar charts are drawn against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum XAxis {
+    /// Job size, with a series per prover
+    Size,
+    /// The machine results were produced on, with a chart per job and prover
+    /// and a series per job size
+    Machine,
+    /// Core count of machine types such as `e2-standard-4`, with a chart per
+    /// job and prover and a series per machine class and job size
+    Cores,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Scale {
+    Linear,
+    Log,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub enum Metric {
+    ProveTime,
+    VerifyTime,
+    ProofSize,
+    /// Peak heap of every job, from the `allocations.csv` written next to the
+    /// results by hosts built with `count-alloc`
+    Memory,
+}
+
+const METRICS: [Metric; 4] = [
+    Metric::ProveTime,
+    Metric::VerifyTime,
+    Metric::ProofSize,
+    Metric::Memory,
+];
+
+impl Metric {
+    // Stem of the chart files, as named before metrics could be selected
+    fn file_stem(&self) -> &'static str {
+        match self {
+            Self::ProveTime => "proving_time",
+            Self::VerifyTime => "verifying_time",
+            Self::ProofSize => "proof_size",
+            Self::Memory => "peak_memory",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ProveTime => "Prover time",
+            Self::VerifyTime => "Verifier time",
+            Self::ProofSize => "Proof size",
+            Self::Memory => "Peak heap",
+        }
+    }
+
+    fn unit(&self) -> &'static str {
+        match self {
+            Self::ProveTime => "s",
+            Self::VerifyTime => "ms",
+            Self::ProofSize => "bytes",
+            Self::Memory => "MB",
+        }
+    }
+}
+
+fn parse_size(s: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("expected WIDTHxHEIGHT, got {:?}", s);
+    let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+    let width = width.parse().map_err(|_| invalid())?;
+    let height = height.parse().map_err(|_| invalid())?;
+    Ok((width, height))
+}
+
+#[derive(clap::Args)]
+pub struct PlotOptions {
+    /// Directory to write the charts to
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub out_dir: PathBuf,
+
+    /// Image format of the charts
+    #[arg(long, value_enum, default_value_t = ImageFormat::Png)]
+    pub format: ImageFormat,
+
+    /// Metrics to chart; every metric when omitted
+    #[arg(long = "metric", value_enum, value_name = "METRIC")]
+    pub metrics: Vec<Metric>,
+
+    /// Jobs to chart; every job when omitted
+    #[arg(long = "job", value_name = "NAME")]
+    pub jobs: Vec<String>,
+
+    /// Provers to draw; every prover when omitted
+    #[arg(long = "prover", value_name = "NAME")]
+    pub provers: Vec<String>,
+
+    /// Line chart of the medians, box plot of the samples, grouped bars, or
+    /// prove time against the other metrics
+    #[arg(long, value_enum, default_value_t = Kind::Line)]
+    pub kind: Kind,
+
+    /// Range of the samples drawn around the medians of line charts, where a
+    /// job was run more than once
+    #[arg(long, value_enum, default_value_t = Spread::Bars)]
+    pub spread: Spread,
+
+    /// Divide every value by the median of this prover at the same job size,
+    /// e.g. to compare CpuPoseidon against CpuSHA256; the baseline is drawn
+    /// even when filtered out with `--prover`
+    #[arg(long, value_name = "PROVER")]
+    pub baseline: Option<String>,
+
+    /// What to draw line, box and bar charts against; results files of
+    /// several machines are told apart by a `machine` column or a
+    /// `MACHINE=FILE` label
+    #[arg(long, value_enum, default_value_t = XAxis::Size)]
+    pub x_axis: XAxis,
+
+    /// TOML table of machine prices in US dollars per hour, such as
+    /// `e2-standard-4 = 0.134`, to also chart what proving and verifying cost
+    /// on each machine
+    #[arg(long, value_name = "FILE")]
+    pub prices: Option<PathBuf>,
+
+    /// Scale of the job size or core count axis, or of prove time in scatter
+    /// charts; box and bar charts space job sizes evenly instead
+    #[arg(long, value_enum, default_value_t = Scale::Linear)]
+    pub x_scale: Scale,
+
+    /// Scale of the metric axis
+    #[arg(long, value_enum, default_value_t = Scale::Linear)]
+    pub y_scale: Scale,
+
+    /// Image size in pixels
+    #[arg(long, value_name = "WIDTHxHEIGHT", default_value = "1024x768", value_parser = parse_size)]
+    pub size: (u32, u32),
+
+    /// Chart title, in which `{metric}`, `{job}`, `{size}` in scatter charts
+    /// and `{prover}` in machine charts are replaced [default: "{metric}
+    /// {job}", followed by the size or prover]
+    #[arg(long, value_name = "TEMPLATE")]
+    pub title: Option<String>,
+}
+
+impl PlotOptions {
+    pub fn selects(&self, job: &str, prover: &str) -> bool {
+        (self.jobs.is_empty() || self.jobs.iter().any(|j| j == job))
+            && (self.provers.is_empty()
+                || self.provers.iter().any(|p| p == prover)
+                || self.baseline.as_deref() == Some(prover))
+    }
+
+    /// Fills in the title template, or `default` when none was given
+    pub fn title(&self, default: &str, fields: &[(&str, &str)]) -> String {
+        let template = self.title.as_deref().unwrap_or(default);
+        (fields.iter()).fold(String::from(template), |title, (field, value)| {
+            title.replace(&format!("{{{}}}", field), value)
+        })
+    }
+}
+
+/// Samples at every x value, sorted. The x value is a job size, or the index
+/// or core count of a machine.
+type Samples = BTreeMap<usize, Vec<f32>>;
+
+type Table = BTreeMap<(String, String), Samples>;
+
+fn table(points: impl Iterator<Item = (String, String, usize, f32)>) -> Table {
+    let mut table = Table::new();
+    for (job, prover, size, value) in points {
+        table
+            .entry((job, prover))
+            .or_default()
+            .entry(size)
+            .or_default()
+            .push(value);
+    }
+    for samples in table.values_mut().flat_map(|s| s.values_mut()) {
+        samples.sort_by(|a, b| a.total_cmp(b));
+    }
+    table
+}
+
+// Linearly interpolated percentile of sorted samples.
+fn percentile(sorted: &[f32], p: f32) -> f32 {
+    let rank = p * (sorted.len() - 1) as f32;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f32)
+}
+
+/// Five-number summary of the samples of one job size, for box plots.
+struct Summary {
+    q1: f32,
+    median: f32,
+    q3: f32,
+    // Furthest samples within 1.5 IQR of the box
+    whiskers: (f32, f32),
+    outliers: Vec<f32>,
+}
+
+impl Summary {
+    fn new(sorted: &[f32]) -> Self {
+        let q1 = percentile(sorted, 0.25);
+        let q3 = percentile(sorted, 0.75);
+        let fences = (q1 - 1.5 * (q3 - q1), q3 + 1.5 * (q3 - q1));
+        let inside = |v: &&f32| fences.0 <= **v && **v <= fences.1;
+        Summary {
+            q1,
+            median: percentile(sorted, 0.5),
+            q3,
+            whiskers: (
+                *sorted.iter().find(inside).unwrap(),
+                *sorted.iter().rev().find(inside).unwrap(),
+            ),
+            outliers: sorted.iter().copied().filter(|v| !inside(&v)).collect(),
+        }
+    }
+}
+
+fn metric_table(metric: Metric, rows: &[Row], memory: &[MemoryRow]) -> Table {
+    let point = |row: &Row, value: f32| {
+        (
+            row.job_name.clone(),
+            row.prover.clone(),
+            row.job_size,
+            value,
+        )
+    };
+    match metric {
+        Metric::ProveTime => table(rows.iter().map(|r| point(r, r.proof_secs))),
+        Metric::VerifyTime => table(rows.iter().map(|r| point(r, r.verify_millis))),
+        Metric::ProofSize => table(rows.iter().map(|r| point(r, r.proof_bytes))),
+        Metric::Memory => table(memory.iter().map(|m| {
+            (
+                m.job_name.clone(),
+                m.prover.clone(),
+                m.job_size,
+                m.peak_bytes as f32 / (1 << 20) as f32,
+            )
+        })),
+    }
+}
+
+// Divides every sample by the baseline's median at the same job size. Sizes
+// the baseline did not run, and jobs it did not run at all, are dropped.
+fn normalize(table: &mut Table, baseline: &str) {
+    let medians: BTreeMap<(String, usize), f32> = table
+        .iter()
+        .filter(|((_, prover), _)| prover == baseline)
+        .flat_map(|((job, _), samples)| {
+            (samples.iter()).map(|(size, v)| ((job.clone(), *size), percentile(v, 0.5)))
+        })
+        .filter(|(_, median)| *median > 0.0)
+        .collect();
+
+    let jobs: BTreeSet<String> = table.keys().map(|(job, _)| job.clone()).collect();
+    for job in jobs {
+        if !medians.keys().any(|(j, _)| *j == job) {
+            eprintln!("No {} results for {}; skipping it", baseline, job);
+        }
+    }
+
+    for ((job, _), samples) in table.iter_mut() {
+        samples.retain(|size, _| medians.contains_key(&(job.clone(), *size)));
+        for (size, v) in samples.iter_mut() {
+            let median = medians[&(job.clone(), *size)];
+            v.iter_mut().for_each(|x| *x /= median);
+        }
+    }
+    table.retain(|_, samples| !samples.is_empty());
+}
+
+// Legend label of a prover's series; provers of systems with a single prover
+// are named after their system, and with their security level when it is
+// part of the name.
+fn series_label(prover: &str) -> String {
+    if let Some(bits) = prover.strip_prefix("miden-") {
+        return format!("{} ({} bits)", tables::system_name(prover), bits);
+    }
+    match (tables::system_name(prover), prover) {
+        (system, "miden" | "risczero") => String::from(system),
+        (system, prover) if system != prover => format!("{} {}", system, prover),
+        _ => String::from(prover),
+    }
+}
+
+/// One line of a chart. `color` indexes the palette, so that a prover keeps
+/// its color across charts even where other provers are absent.
+struct Series<'a> {
+    label: String,
+    color: usize,
+    samples: &'a Samples,
+}
+
+struct Chart<'a> {
+    title: String,
+    x_label: String,
+    y_label: String,
+    series: Vec<Series<'a>>,
+    // Every x value of any series, in order, and its tick label
+    xs: Vec<usize>,
+    ticks: Vec<String>,
+    // Whether the x values are evenly spaced categories rather than numbers
+    categorical: bool,
+}
+
+impl Chart<'_> {
+    // Position of an x value on the axis: its index among categories
+    fn position(&self, x: usize) -> f32 {
+        if self.categorical {
+            self.xs.binary_search(&x).unwrap() as f32
+        } else {
+            x as f32
+        }
+    }
+}
+
+/// One prover configuration of a scatter chart, at the medians of its samples.
+struct Config {
+    label: String,
+    color: usize,
+    prove_secs: f32,
+    value: f32,
+    // Whether no other configuration is at least as good on both axes and
+    // better on one
+    optimal: bool,
+}
+
+struct Tradeoff {
+    title: String,
+    y_label: String,
+    configs: Vec<Config>,
+}
+
+fn mark_pareto(configs: &mut [Config]) {
+    let points: Vec<(f32, f32)> = configs.iter().map(|c| (c.prove_secs, c.value)).collect();
+    for config in configs {
+        let (x, y) = (config.prove_secs, config.value);
+        config.optimal = !points
+            .iter()
+            .any(|&(a, b)| a <= x && b <= y && (a < x || b < y));
+    }
+}
+
+enum Figure<'a> {
+    Chart(Chart<'a>),
+    Tradeoff(Tradeoff),
+}
+
+impl Figure<'_> {
+    fn title(&self) -> &str {
+        match self {
+            Figure::Chart(chart) => &chart.title,
+            Figure::Tradeoff(tradeoff) => &tradeoff.title,
+        }
+    }
+
+    fn x_label(&self) -> String {
+        match self {
+            Figure::Chart(chart) => chart.x_label.clone(),
+            Figure::Tradeoff(_) => {
+                let metric = Metric::ProveTime;
+                format!("{} ({})", metric.name(), metric.unit())
+            }
+        }
+    }
+
+    fn y_label(&self) -> &str {
+        match self {
+            Figure::Chart(chart) => &chart.y_label,
+            Figure::Tradeoff(tradeoff) => &tradeoff.y_label,
+        }
+    }
+}
+
+// Axis bounds covering `values`. A log axis cannot start at zero, and a
+// single value would give an empty range.
+fn bounds(values: impl Iterator<Item = f32>, scale: Scale, from_zero: bool) -> (f32, f32) {
+    let (min, max) = values.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    match scale {
+        Scale::Linear if from_zero => (0.0, max * 1.5),
+        Scale::Linear if min < max => (min, max),
+        Scale::Linear => (min - 1.0, max + 1.0),
+        Scale::Log if min < max && min > 0.0 => (min / 1.5, max * 1.5),
+        Scale::Log => (min.max(f32::MIN_POSITIVE) / 2.0, max * 2.0),
+    }
+}
+
+// Log axes tick every integer between powers of ten, so whole numbers are
+// kept short.
+fn label(v: &f32) -> String {
+    if v.fract() == 0.0 {
+        format!("{:.0}", v)
+    } else {
+        format!("{:.1}", v)
+    }
+}
+
+type Context<'a, DB, X, Y> = ChartContext<'a, DB, Cartesian2d<X, Y>>;
+
+fn draw_lines<DB, X, Y>(cc: &mut Context<DB, X, Y>, chart: &Chart, spread: Spread)
+where
+    DB: DrawingBackend,
+    X: Ranged<ValueType = f32>,
+    Y: Ranged<ValueType = f32>,
+{
+    for series in &chart.series {
+        let color = series.color;
+        let style = Palette99::pick(color).to_rgba();
+        // (x, smallest, median, largest sample)
+        let ranges: Vec<(f32, f32, f32, f32)> = series
+            .samples
+            .iter()
+            .map(|(x, v)| (chart.position(*x), v[0], percentile(v, 0.5), v[v.len() - 1]))
+            .collect();
+
+        match spread {
+            Spread::None => {}
+            Spread::Bars => {
+                // Jobs run once have nothing to show
+                let bars = (ranges.iter()).filter(|(_, min, _, max)| min < max).map(
+                    |&(x, min, median, max)| {
+                        ErrorBar::new_vertical(x, min, median, max, style.filled(), 10)
+                    },
+                );
+                cc.draw_series(bars).unwrap();
+            }
+            Spread::Band => {
+                let band: Vec<(f32, f32)> = (ranges.iter().map(|(x, _, _, max)| (*x, *max)))
+                    .chain(ranges.iter().rev().map(|(x, min, _, _)| (*x, *min)))
+                    .collect();
+                cc.draw_series(std::iter::once(Polygon::new(band, style.mix(0.2))))
+                    .unwrap();
+            }
+        }
+
+        let medians = ranges.iter().map(|(x, _, median, _)| (*x, *median));
+        cc.draw_series(LineSeries::new(medians, &style))
+            .unwrap()
+            .label(&series.label)
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 20, y)], Palette99::pick(color))
+            });
+    }
+}
+
+// Boxes and bars of the same x value sit side by side around its position on
+// the x axis, which is the index of the value in `chart.xs`. Returns the
+// center of each and their width.
+fn slots<'a>(chart: &'a Chart) -> impl Iterator<Item = (&'a Series<'a>, Vec<f32>, f32)> {
+    let width = 0.8 / chart.series.len() as f32;
+    chart.series.iter().enumerate().map(move |(index, series)| {
+        let offset = (index as f32 + 0.5) * width - 0.4;
+        let positions = (series.samples.keys())
+            .map(|x| chart.position(*x) + offset)
+            .collect();
+        (series, positions, width)
+    })
+}
+
+// An empty series, to give a prover an entry in the legend
+fn legend_entry<DB, X, Y>(cc: &mut Context<DB, X, Y>, series: &Series)
+where
+    DB: DrawingBackend,
+    X: Ranged<ValueType = f32>,
+    Y: Ranged<ValueType = f32>,
+{
+    let color = series.color;
+    cc.draw_series(std::iter::empty::<Rectangle<(f32, f32)>>())
+        .unwrap()
+        .label(&series.label)
+        .legend(move |(x, y)| {
+            Rectangle::new(
+                [(x, y - 5), (x + 20, y + 5)],
+                Palette99::pick(color).filled(),
+            )
+        });
+}
+
+fn draw_bars<DB, X, Y>(cc: &mut Context<DB, X, Y>, chart: &Chart)
+where
+    DB: DrawingBackend,
+    X: Ranged<ValueType = f32>,
+    Y: Ranged<ValueType = f32>,
+{
+    // Bars on a log axis start from its bottom
+    let base = cc.y_range().start;
+    for (series, positions, width) in slots(chart) {
+        let style = Palette99::pick(series.color).to_rgba();
+        let bars = (series.samples.values().zip(positions)).map(|(samples, position)| {
+            let (left, right) = (position - width * 0.45, position + width * 0.45);
+            Rectangle::new(
+                [(left, base), (right, percentile(samples, 0.5))],
+                style.filled(),
+            )
+        });
+        cc.draw_series(bars).unwrap();
+        legend_entry(cc, series);
+    }
+}
+
+fn draw_boxes<DB, X, Y>(cc: &mut Context<DB, X, Y>, chart: &Chart)
+where
+    DB: DrawingBackend,
+    X: Ranged<ValueType = f32>,
+    Y: Ranged<ValueType = f32>,
+{
+    for (series, positions, width) in slots(chart) {
+        let style = Palette99::pick(series.color).to_rgba();
+
+        for (samples, position) in series.samples.values().zip(positions) {
+            let (left, right) = (position - width * 0.4, position + width * 0.4);
+            let summary = Summary::new(samples);
+            let (low, high) = summary.whiskers;
+
+            let boxes = [
+                Rectangle::new(
+                    [(left, summary.q1), (right, summary.q3)],
+                    style.mix(0.3).filled(),
+                ),
+                Rectangle::new(
+                    [(left, summary.q1), (right, summary.q3)],
+                    style.stroke_width(1),
+                ),
+            ];
+            cc.draw_series(boxes).unwrap();
+            let lines = [
+                vec![(left, summary.median), (right, summary.median)],
+                vec![(position, summary.q3), (position, high)],
+                vec![(position, summary.q1), (position, low)],
+                vec![(left, high), (right, high)],
+                vec![(left, low), (right, low)],
+            ];
+            cc.draw_series(lines.into_iter().map(|l| PathElement::new(l, style)))
+                .unwrap();
+            let outliers = summary
+                .outliers
+                .iter()
+                .map(|v| Circle::new((position, *v), 3, style));
+            cc.draw_series(outliers).unwrap();
+        }
+
+        legend_entry(cc, series);
+    }
+}
+
+fn draw_tradeoff<DB, X, Y>(cc: &mut Context<DB, X, Y>, tradeoff: &Tradeoff)
+where
+    DB: DrawingBackend,
+    X: Ranged<ValueType = f32>,
+    Y: Ranged<ValueType = f32>,
+{
+    // The frontier steps from each optimal configuration to the next faster
+    // one, bounding the region no configuration reaches
+    let mut optimal: Vec<(f32, f32)> = (tradeoff.configs.iter())
+        .filter(|c| c.optimal)
+        .map(|c| (c.prove_secs, c.value))
+        .collect();
+    optimal.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let steps: Vec<(f32, f32)> = (optimal.windows(2))
+        .flat_map(|w| [w[0], (w[1].0, w[0].1)])
+        .chain(optimal.last().copied())
+        .collect();
+    let style = BLACK.mix(0.5);
+    cc.draw_series(std::iter::once(PathElement::new(steps, style)))
+        .unwrap()
+        .label("Pareto frontier")
+        .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], style));
+
+    // Optimal configurations are filled, dominated ones hollow and greyed
+    for config in &tradeoff.configs {
+        let color = Palette99::pick(config.color).to_rgba();
+        let (marker, text) = if config.optimal {
+            (color.filled(), BLACK.to_rgba())
+        } else {
+            (color.stroke_width(2), BLACK.mix(0.5))
+        };
+        let point = EmptyElement::at((config.prove_secs, config.value))
+            + Circle::new((0, 0), 6, marker)
+            + Text::new(
+                config.label.clone(),
+                (10, -20),
+                ("sans-serif", 16).into_font().color(&text),
+            );
+        cc.draw_series(std::iter::once(point)).unwrap();
+    }
+}
+
+fn draw<DB, X, Y>(area: &DrawingArea<DB, Shift>, x: X, y: Y, figure: &Figure, options: &PlotOptions)
+where
+    DB: DrawingBackend,
+    X: Ranged<ValueType = f32> + ValueFormatter<f32>,
+    Y: Ranged<ValueType = f32> + ValueFormatter<f32>,
+{
+    area.fill(&WHITE).unwrap();
+    let area = area.titled(figure.title(), ("sans-serif", 60)).unwrap();
+
+    let mut cc = ChartBuilder::on(&area)
+        .margin(5)
+        .x_label_area_size(60)
+        .y_label_area_size(70)
+        .build_cartesian_2d(x, y)
+        .unwrap();
+
+    // Categorical axes label each position with its job size or machine
+    let ticks: &[String] = match figure {
+        Figure::Chart(chart) if chart.categorical => &chart.ticks,
+        _ => &[],
+    };
+    let tick_label = |v: &f32| match ticks.get(v.round() as usize) {
+        Some(tick) if (v - v.round()).abs() < 1e-3 => tick.clone(),
+        _ => String::new(),
+    };
+    let x_label = figure.x_label();
+    let mut mesh = cc.configure_mesh();
+    mesh.y_labels(10)
+        .disable_mesh()
+        .x_desc(&x_label)
+        .y_desc(figure.y_label())
+        .y_label_formatter(&label);
+    if !ticks.is_empty() {
+        mesh.x_labels(ticks.len() + 1)
+            .x_label_formatter(&tick_label);
+    } else {
+        mesh.x_labels(20).x_label_formatter(&label);
+    }
+    mesh.draw().unwrap();
+
+    match (figure, options.kind) {
+        (Figure::Tradeoff(tradeoff), _) => draw_tradeoff(&mut cc, tradeoff),
+        (Figure::Chart(chart), Kind::Box) => draw_boxes(&mut cc, chart),
+        (Figure::Chart(chart), Kind::Bar) => draw_bars(&mut cc, chart),
+        (Figure::Chart(chart), _) => draw_lines(&mut cc, chart, options.spread),
+    }
+
+    // The baseline's own median, for reference
+    if options.baseline.is_some() && matches!(figure, Figure::Chart(_)) {
+        let x = cc.x_range();
+        let line = PathElement::new(vec![(x.start, 1.0), (x.end, 1.0)], BLACK);
+        cc.draw_series(std::iter::once(line)).unwrap();
+    }
+
+    cc.configure_series_labels()
+        .border_style(BLACK)
+        .draw()
+        .unwrap();
+
+    area.present().expect("Unable to write result to file");
+}
+
+// Plotters has a distinct coordinate type per scale, hence one call per
+// combination.
+fn draw_scaled<DB: DrawingBackend>(
+    area: &DrawingArea<DB, Shift>,
+    figure: &Figure,
+    options: &PlotOptions,
+) {
+    let ((x0, x1, x_scale), (y0, y1)) = match figure {
+        Figure::Chart(chart) => {
+            let values = (chart.series.iter())
+                .flat_map(|s| s.samples.values())
+                .flatten()
+                .copied();
+            let x = if chart.categorical {
+                (-0.5, chart.xs.len() as f32 - 0.5, Scale::Linear)
+            } else {
+                let xs = chart.xs.iter().map(|x| *x as f32);
+                let (x0, x1) = bounds(xs, options.x_scale, false);
+                (x0, x1, options.x_scale)
+            };
+            (x, bounds(values, options.y_scale, true))
+        }
+        // Both axes start from zero, leaving room for the labels of the points
+        Figure::Tradeoff(tradeoff) => {
+            let configs = tradeoff.configs.iter();
+            let (x0, x1) = bounds(configs.clone().map(|c| c.prove_secs), options.x_scale, true);
+            let y = bounds(configs.map(|c| c.value), options.y_scale, true);
+            ((x0, x1, options.x_scale), y)
+        }
+    };
+    let linear = |a: f32, b: f32| RangedCoordf32::from(a..b);
+    let log = |a: f32, b: f32| LogCoord::from((a..b).log_scale());
+
+    match (x_scale, options.y_scale) {
+        (Scale::Linear, Scale::Linear) => {
+            draw(area, linear(x0, x1), linear(y0, y1), figure, options)
+        }
+        (Scale::Linear, Scale::Log) => draw(area, linear(x0, x1), log(y0, y1), figure, options),
+        (Scale::Log, Scale::Linear) => draw(area, log(x0, x1), linear(y0, y1), figure, options),
+        (Scale::Log, Scale::Log) => draw(area, log(x0, x1), log(y0, y1), figure, options),
+    }
+}
+
+/// Receives every chart drawn, with its job and file name without extension.
+type Sink<'s> = dyn FnMut(&str, String, &Figure) + 's;
+
+/// Anything drawn as a chart file, on whichever backend its format needs.
+pub trait Draw {
+    fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>, options: &PlotOptions);
+}
+
+impl Draw for Figure<'_> {
+    fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>, options: &PlotOptions) {
+        draw_scaled(area, self, options)
+    }
+}
+
+/// Draws `drawing` into `<out_dir>/<name>` in the format and at the size of
+/// `options`.
+pub fn render(name: &str, drawing: &impl Draw, options: &PlotOptions) {
+    let path = options
+        .out_dir
+        .join(format!("{}.{}", name, options.format.extension()));
+    match options.format {
+        ImageFormat::Png => {
+            let area = BitMapBackend::new(&path, options.size).into_drawing_area();
+            drawing.draw(&area, options)
+        }
+        ImageFormat::Svg => {
+            let area = SVGBackend::new(&path, options.size).into_drawing_area();
+            drawing.draw(&area, options)
+        }
+    }
+}
+
+// Draws the median prove time against the median of every other selected
+// metric, one chart per metric, job and job size, with a point for every
+// prover and security level it ran at.
+fn plot_tradeoffs(rows: &[Row], provers: &[&str], options: &PlotOptions, sink: &mut Sink) {
+    if options.baseline.is_some() {
+        eprintln!("Scatter charts are not normalized; ignoring the baseline");
+    }
+    let metrics: Vec<Metric> = if options.metrics.is_empty() {
+        vec![Metric::VerifyTime, Metric::ProofSize]
+    } else {
+        let mut metrics = options.metrics.clone();
+        metrics.sort();
+        metrics.dedup();
+        if metrics.contains(&Metric::Memory) {
+            eprintln!("Scatter charts do not cover peak heap; skipping it");
+        }
+        metrics.retain(|m| matches!(m, Metric::VerifyTime | Metric::ProofSize));
+        metrics
+    };
+
+    // Prove times and values of the metric of every configuration, by job and
+    // job size
+    type Configs<'a> = BTreeMap<(&'a str, Option<u32>), (Vec<f32>, Vec<f32>)>;
+    for metric in metrics {
+        let mut groups: BTreeMap<(&str, usize), Configs> = BTreeMap::new();
+        for row in rows {
+            let value = match metric {
+                Metric::VerifyTime => row.verify_millis,
+                _ => row.proof_bytes,
+            };
+            let (prove_secs, values) = groups
+                .entry((&row.job_name, row.job_size))
+                .or_default()
+                .entry((&row.prover, row.security_bits))
+                .or_default();
+            prove_secs.push(row.proof_secs);
+            values.push(value);
+        }
+
+        for ((job, size), configs) in groups {
+            // Levels are only named for provers run at more than one
+            let levels = |prover: &str| configs.keys().filter(|(p, _)| *p == prover).count();
+            let median = |mut v: Vec<f32>| {
+                v.sort_by(|a, b| a.total_cmp(b));
+                percentile(&v, 0.5)
+            };
+            let mut configs: Vec<Config> = (configs.iter())
+                .map(|(&(prover, bits), (prove_secs, values))| Config {
+                    label: match bits {
+                        Some(bits) if levels(prover) > 1 => {
+                            format!("{} ({} bits)", series_label(prover), bits)
+                        }
+                        None if levels(prover) > 1 => {
+                            format!("{} (level unknown)", series_label(prover))
+                        }
+                        _ => series_label(prover),
+                    },
+                    color: provers.binary_search(&prover).unwrap(),
+                    prove_secs: median(prove_secs.clone()),
+                    value: median(values.clone()),
+                    optimal: false,
+                })
+                .collect();
+            mark_pareto(&mut configs);
+
+            let tradeoff = Tradeoff {
+                title: options.title(
+                    "{metric} {job} {size}",
+                    &[
+                        ("metric", metric.file_stem()),
+                        ("job", job),
+                        ("size", &size.to_string()),
+                    ],
+                ),
+                y_label: format!("{} ({})", metric.name(), metric.unit()),
+                configs,
+            };
+            let name = format!("tradeoff_{}_{}_{}", metric.file_stem(), job, size);
+            sink(job, name, &Figure::Tradeoff(tradeoff));
+        }
+    }
+}
+
+// Core count of a machine type such as `e2-standard-4`, and its class
+fn cores(machine: &str) -> Option<(&str, usize)> {
+    let (class, cores) = machine.rsplit_once('-')?;
+    Some((class, cores.parse().ok()?))
+}
+
+/// How one value of a machine chart is taken from a result, if it can be.
+type Value<'a> = Box<dyn Fn(&Row) -> Option<f32> + 'a>;
+
+// Draws every selected metric of each job and prover against the machines
+// the results were produced on. With a price table, durations are also drawn
+// as what a thousand runs cost.
+fn plot_machines(rows: &[Row], metrics: &BTreeSet<Metric>, options: &PlotOptions, sink: &mut Sink) {
+    if options.baseline.is_some() {
+        eprintln!("Machine charts are not normalized; ignoring the baseline");
+    }
+    if options.metrics.contains(&Metric::Memory) {
+        eprintln!("Peak heap is not recorded per machine; skipping it");
+    }
+    let prices = options.prices.as_deref().map(Prices::load);
+
+    // Machines in order of core count, then name
+    let mut machines: Vec<&str> = (rows.iter())
+        .map(|r| r.machine.as_str())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    machines.sort_by_key(|m| cores(m).map_or(usize::MAX, |(_, cores)| cores));
+    if options.x_axis == XAxis::Cores {
+        let unknown: Vec<&str> = (machines.iter().copied())
+            .filter(|m| cores(m).is_none())
+            .collect();
+        if !unknown.is_empty() {
+            eprintln!("No core count in {}; leaving them out", unknown.join(", "));
+        }
+    }
+    if let Some(prices) = &prices {
+        let unpriced: Vec<&str> = (machines.iter().copied())
+            .filter(|m| prices.hourly(m).is_none())
+            .collect();
+        if !unpriced.is_empty() {
+            eprintln!(
+                "No price for {}; leaving them out of costs",
+                unpriced.join(", ")
+            );
+        }
+    }
+
+    // Stem, y axis label and value of every chart drawn per job and prover
+    let mut variants: Vec<(&str, String, Value)> = Vec::new();
+    for metric in metrics {
+        let value: Value = match metric {
+            Metric::ProveTime => Box::new(|r: &Row| Some(r.proof_secs)),
+            Metric::VerifyTime => Box::new(|r: &Row| Some(r.verify_millis)),
+            Metric::ProofSize => Box::new(|r: &Row| Some(r.proof_bytes)),
+            Metric::Memory => continue,
+        };
+        let label = format!("{} ({})", metric.name(), metric.unit());
+        variants.push((metric.file_stem(), label, value));
+
+        let Some(prices) = &prices else {
+            continue;
+        };
+        let thousand =
+            |machine: &str, secs: f32| Some(prices.cost(machine, secs as f64)? as f32 * 1000.0);
+        match metric {
+            Metric::ProveTime => variants.push((
+                "proving_cost",
+                String::from("Proving cost ($ per 1000 proofs)"),
+                Box::new(move |r: &Row| thousand(&r.machine, r.proof_secs)),
+            )),
+            Metric::VerifyTime => variants.push((
+                "verifying_cost",
+                String::from("Verifying cost ($ per 1000 verifications)"),
+                Box::new(move |r: &Row| thousand(&r.machine, r.verify_millis / 1000.0)),
+            )),
+            _ => {}
+        }
+    }
+
+    for (stem, y_label, value) in variants {
+        // Samples by job and prover, then by job size and machine class
+        type Lines<'a> = BTreeMap<(usize, &'a str), Samples>;
+        let mut table: BTreeMap<(&str, &str), Lines> = BTreeMap::new();
+        for row in rows {
+            let (class, x) = match options.x_axis {
+                XAxis::Cores => match cores(&row.machine) {
+                    Some((class, cores)) => (class, cores),
+                    None => continue,
+                },
+                _ => {
+                    let index = machines.iter().position(|m| *m == row.machine);
+                    ("", index.unwrap())
+                }
+            };
+            let Some(v) = value(row) else {
+                continue;
+            };
+            (table.entry((&row.job_name, &row.prover)).or_default())
+                .entry((row.job_size, class))
+                .or_default()
+                .entry(x)
+                .or_default()
+                .push(v);
+        }
+
+        for ((job, prover), mut lines) in table {
+            for samples in lines.values_mut().flat_map(|s| s.values_mut()) {
+                samples.sort_by(|a, b| a.total_cmp(b));
+            }
+            let series: Vec<Series> = (lines.iter().enumerate())
+                .map(|(color, ((size, class), samples))| Series {
+                    label: match *class {
+                        "" => format!("size {}", size),
+                        class => format!("{}, size {}", class, size),
+                    },
+                    color,
+                    samples,
+                })
+                .collect();
+            let xs: Vec<usize> = (series.iter())
+                .flat_map(|s| s.samples.keys().copied())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let (axis, x_label, ticks, categorical) = match options.x_axis {
+                XAxis::Cores => (
+                    "cores",
+                    "Cores",
+                    xs.iter().map(|x| x.to_string()).collect(),
+                    options.kind.categorical(),
+                ),
+                _ => (
+                    "machines",
+                    "Machine",
+                    xs.iter().map(|x| String::from(machines[*x])).collect(),
+                    true,
+                ),
+            };
+            let chart = Chart {
+                title: options.title(
+                    "{metric} {job} {prover}",
+                    &[("metric", stem), ("job", job), ("prover", prover)],
+                ),
+                x_label: String::from(x_label),
+                y_label: y_label.clone(),
+                series,
+                xs,
+                ticks,
+                categorical,
+            };
+
+            let name = format!("{}_{}_{}_{}", axis, stem, job, prover);
+            sink(job, name, &Figure::Chart(chart));
+        }
+    }
+}
+
+/// Draws one chart per selected metric and job into `options.out_dir`.
+/// Provers without data for a job are left out of its chart. Jobs of the
+/// same name share a chart whichever system ran them, so results files of
+/// several systems can be passed together. Scatter charts are drawn per job
+/// size instead, and named `tradeoff_<metric>_<job>_<size>`.
+pub fn plot_all(rows: &[Row], memory: &[MemoryRow], options: &PlotOptions) {
+    create_out_dir(options);
+    draw_all(rows, memory, options, &mut |_, name, figure| {
+        render(&name, figure, options)
+    });
+}
+
+pub fn create_out_dir(options: &PlotOptions) {
+    std::fs::create_dir_all(&options.out_dir).unwrap_or_else(|e| {
+        panic!(
+            "Could not create output directory {}: {}",
+            options.out_dir.display(),
+            e
+        )
+    });
+}
+
+/// One chart drawn as an SVG document.
+pub struct Svg {
+    pub job: String,
+    pub name: String,
+    pub svg: String,
+}
+
+/// Draws the charts `plot_all` would, as SVG documents kept in memory.
+pub fn svg_all(rows: &[Row], memory: &[MemoryRow], options: &PlotOptions) -> Vec<Svg> {
+    let mut charts = Vec::new();
+    draw_all(rows, memory, options, &mut |job, name, figure| {
+        let mut svg = String::new();
+        {
+            let area = SVGBackend::with_string(&mut svg, options.size).into_drawing_area();
+            draw_scaled(&area, figure, options);
+        }
+        charts.push(Svg {
+            job: String::from(job),
+            name,
+            svg,
+        });
+    });
+    charts
+}
+
+fn draw_all(rows: &[Row], memory: &[MemoryRow], options: &PlotOptions, sink: &mut Sink) {
+    let rows: Vec<Row> = rows
+        .iter()
+        .filter(|r| options.selects(&r.job_name, &r.prover))
+        .cloned()
+        .collect();
+    let memory: Vec<MemoryRow> = memory
+        .iter()
+        .filter(|m| options.selects(&m.job_name, &m.prover))
+        .cloned()
+        .collect();
+
+    let provers: Vec<&str> = rows
+        .iter()
+        .map(|r| r.prover.as_str())
+        .chain(memory.iter().map(|m| m.prover.as_str()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let metrics: BTreeSet<Metric> = if options.metrics.is_empty() {
+        METRICS.into_iter().collect()
+    } else {
+        options.metrics.iter().copied().collect()
+    };
+
+    if options.kind == Kind::Scatter {
+        if options.x_axis != XAxis::Size {
+            eprintln!("Scatter charts are drawn per job size; ignoring --x-axis");
+        }
+        plot_tradeoffs(&rows, &provers, options, sink);
+        return;
+    }
+    if options.x_axis != XAxis::Size {
+        plot_machines(&rows, &metrics, options, sink);
+        return;
+    }
+    if options.prices.is_some() {
+        eprintln!("Prices only apply to charts against machines; ignoring them");
+    }
+
+    for metric in metrics {
+        let mut table = metric_table(metric, &rows, &memory);
+        if table.is_empty() && metric == Metric::Memory {
+            eprintln!("No allocations.csv next to the results; skipping memory charts");
+        }
+        let y_label = match &options.baseline {
+            Some(baseline) => {
+                normalize(&mut table, baseline);
+                format!("{} relative to {}", metric.name(), series_label(baseline))
+            }
+            None => format!("{} ({})", metric.name(), metric.unit()),
+        };
+        let jobs: BTreeSet<&String> = table.keys().map(|(job_name, _)| job_name).collect();
+
+        for j in jobs {
+            let series: Vec<Series> = provers
+                .iter()
+                .enumerate()
+                .filter_map(|(color, prover)| {
+                    let samples = table.get(&(j.clone(), String::from(*prover)))?;
+                    Some(Series {
+                        label: series_label(prover),
+                        color,
+                        samples,
+                    })
+                })
+                .collect();
+            let sizes: Vec<usize> = series
+                .iter()
+                .flat_map(|s| s.samples.keys().copied())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            let chart = Chart {
+                title: options.title(
+                    "{metric} {job}",
+                    &[("metric", metric.file_stem()), ("job", j)],
+                ),
+                x_label: String::from("Job size"),
+                y_label: y_label.clone(),
+                series,
+                ticks: sizes.iter().map(|s| s.to_string()).collect(),
+                xs: sizes,
+                categorical: options.kind.categorical(),
+            };
+
+            let name = format!("{}_{}", metric.file_stem(), j);
+            sink(j, name, &Figure::Chart(chart));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn optimal(points: &[(f32, f32)]) -> Vec<bool> {
+        let mut configs: Vec<Config> = (points.iter().enumerate())
+            .map(|(color, &(prove_secs, value))| Config {
+                label: format!("config {}", color),
+                color,
+                prove_secs,
+                value,
+                optimal: false,
+            })
+            .collect();
+        mark_pareto(&mut configs);
+        configs.iter().map(|c| c.optimal).collect()
+    }
+
+    #[test]
+    fn pareto_drops_dominated_points() {
+        // The third is slower and bigger than the second, the fourth only
+        // slower than the first at the same size
+        let points = [(1.0, 10.0), (2.0, 5.0), (3.0, 6.0), (1.5, 10.0), (4.0, 1.0)];
+        assert_eq!(optimal(&points), [true, true, false, false, true]);
+    }
+
+    #[test]
+    fn pareto_keeps_ties() {
+        // Identical points do not dominate one another
+        assert_eq!(optimal(&[(1.0, 2.0), (1.0, 2.0)]), [true, true]);
+        assert_eq!(
+            optimal(&[(1.0, 2.0), (1.0, 2.0), (1.0, 3.0)]),
+            [true, true, false]
+        );
+    }
+
+    #[test]
+    fn pareto_keeps_a_single_point() {
+        assert_eq!(optimal(&[(5.0, 5.0)]), [true]);
+    }
+}
@@ -0,0 +1,282 @@
+// What proofs cost on rented machines: the median prove time of every job,
+// system and machine, priced at the machine's hourly rate. Costs are given
+// per proof and per unit of work, one step of the job size such as one hash
+// of an iterated hashing job, so that sizes and jobs can be compared.
+
+use crate::prices::Prices;
+use crate::results::{median, Row};
+use crate::tables::{system_name, Format};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+const HEADERS: [&str; 6] = [
+    "Machine",
+    "System",
+    "Job size",
+    "Prover time (sec)",
+    "$ per proof",
+    "$ per unit",
+];
+
+struct Line {
+    machine: String,
+    system: String,
+    size: usize,
+    secs: f32,
+    per_proof: f64,
+}
+
+impl Line {
+    fn per_unit(&self) -> f64 {
+        self.per_proof / self.size.max(1) as f64
+    }
+
+    fn cells(&self) -> [String; 6] {
+        [
+            self.machine.clone(),
+            self.system.clone(),
+            self.size.to_string(),
+            format!("{:.2}", self.secs),
+            dollars(self.per_proof),
+            dollars(self.per_unit()),
+        ]
+    }
+}
+
+// At least four significant digits, without an exponent
+fn dollars(v: f64) -> String {
+    let digits = if v > 0.0 {
+        (3 - v.log10().floor() as i32).max(2) as usize
+    } else {
+        2
+    };
+    format!("{:.*}", digits, v)
+}
+
+// Lines of every job, cheapest first within each job size. Results of
+// machines without a price are left out.
+fn costs<'a>(rows: &'a [Row], prices: &Prices) -> BTreeMap<&'a str, Vec<Line>> {
+    let mut provers_per_system: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for r in rows {
+        provers_per_system
+            .entry(system_name(&r.prover))
+            .or_default()
+            .insert(r.prover.as_str());
+    }
+    let label = |prover: &str| {
+        let system = system_name(prover);
+        if provers_per_system[system].len() > 1 {
+            format!("{} ({})", system, prover)
+        } else {
+            String::from(system)
+        }
+    };
+
+    let mut durations: BTreeMap<(&str, &str, &str, usize), Vec<f32>> = BTreeMap::new();
+    let mut unpriced = BTreeSet::new();
+    for r in rows {
+        if prices.hourly(&r.machine).is_none() {
+            unpriced.insert(r.machine.as_str());
+            continue;
+        }
+        durations
+            .entry((&r.job_name, &r.machine, &r.prover, r.job_size))
+            .or_default()
+            .push(r.proof_secs);
+    }
+    if !unpriced.is_empty() {
+        let unpriced: Vec<&str> = unpriced.into_iter().collect();
+        eprintln!("No price for {}; leaving them out", unpriced.join(", "));
+    }
+
+    let mut jobs: BTreeMap<&str, Vec<Line>> = BTreeMap::new();
+    for ((job, machine, prover, size), mut secs) in durations {
+        let secs = median(&mut secs);
+        jobs.entry(job).or_default().push(Line {
+            machine: String::from(machine),
+            system: label(prover),
+            size,
+            secs,
+            per_proof: prices.cost(machine, secs as f64).unwrap(),
+        });
+    }
+    for lines in jobs.values_mut() {
+        lines.sort_by(|a, b| (a.size.cmp(&b.size)).then(a.per_proof.total_cmp(&b.per_proof)));
+    }
+    jobs
+}
+
+fn render_markdown(out: &mut String, lines: &[Line]) {
+    writeln!(out, "| {} |", HEADERS.join(" | ")).unwrap();
+    writeln!(out, "| --- | --- | ---: | ---: | ---: | ---: |").unwrap();
+    for line in lines {
+        writeln!(out, "| {} |", line.cells().join(" | ")).unwrap();
+    }
+}
+
+fn render_html(out: &mut String, lines: &[Line]) {
+    writeln!(out, "<table>").unwrap();
+    writeln!(out, "    <thead>").unwrap();
+    writeln!(out, "        <tr>").unwrap();
+    for header in HEADERS {
+        writeln!(out, "            <th>{}</th>", header).unwrap();
+    }
+    writeln!(out, "        </tr>").unwrap();
+    writeln!(out, "    </thead>").unwrap();
+    writeln!(out, "    <tbody>").unwrap();
+    for line in lines {
+        writeln!(out, "        <tr>").unwrap();
+        for (index, cell) in line.cells().iter().enumerate() {
+            // Machines and systems are text, the rest numbers
+            if index < 2 {
+                writeln!(
+                    out,
+                    "            <td style=\"text-align:left\">{}</td>",
+                    cell
+                )
+                .unwrap();
+            } else {
+                writeln!(out, "            <td>{}</td>", cell).unwrap();
+            }
+        }
+        writeln!(out, "        </tr>").unwrap();
+    }
+    writeln!(out, "    </tbody>").unwrap();
+    writeln!(out, "</table>").unwrap();
+}
+
+/// Render a cost table for every job.
+pub fn render(rows: &[Row], prices: &Prices, format: Format) -> String {
+    let mut out = String::new();
+    for (job, lines) in costs(rows, prices) {
+        writeln!(out, "Cost of {}, in US dollars\n", job).unwrap();
+        match format {
+            Format::Html => render_html(&mut out, &lines),
+            Format::Markdown => render_markdown(&mut out, &lines),
+        }
+        writeln!(out).unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::results::{load_rows, Input};
+    use std::path::PathBuf;
+
+    fn row(machine: &str, size: usize, proof_secs: f32) -> Row {
+        Row {
+            source: PathBuf::from("results.csv"),
+            machine: String::from(machine),
+            prover: String::from("miden"),
+            job_name: String::from("fib"),
+            job_size: size,
+            proof_secs,
+            verify_millis: 1.0,
+            proof_bytes: 1024.0,
+            security_bits: None,
+            threads: None,
+        }
+    }
+
+    fn prices() -> Prices {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prices.toml");
+        std::fs::write(
+            &path,
+            "\"e2-standard-4\" = 0.134\n\"e2-standard-8\" = 0.268\n",
+        )
+        .unwrap();
+        Prices::load(&path)
+    }
+
+    #[test]
+    fn dollars_keep_four_significant_digits() {
+        assert_eq!(dollars(3.2011e-5), "0.00003201");
+        assert_eq!(dollars(0.5), "0.5000");
+        assert_eq!(dollars(12.5), "12.50");
+        assert_eq!(dollars(1234.5), "1234.50");
+        assert_eq!(dollars(0.0), "0.00");
+    }
+
+    #[test]
+    fn prices_the_median_proof() {
+        let rows = [
+            row("e2-standard-4", 10, 2.0),
+            row("e2-standard-4", 10, 0.86),
+            row("e2-standard-4", 10, 0.5),
+        ];
+        let jobs = costs(&rows, &prices());
+        let line = &jobs["fib"][0];
+        assert_eq!(
+            (line.machine.as_str(), line.system.as_str()),
+            ("e2-standard-4", "Miden VM")
+        );
+        assert_eq!(line.secs, 0.86);
+        // 0.86 s at $0.134 an hour
+        assert!((line.per_proof - 3.2011e-5).abs() < 1e-9);
+        assert!((line.per_unit() - 3.2011e-6).abs() < 1e-10);
+        assert_eq!(
+            line.cells(),
+            [
+                "e2-standard-4",
+                "Miden VM",
+                "10",
+                "0.86",
+                "0.00003201",
+                "0.000003201"
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_by_size_then_cost() {
+        let rows = [
+            row("e2-standard-8", 10, 1.0),
+            row("e2-standard-4", 10, 1.5),
+            row("e2-standard-4", 1, 1.0),
+        ];
+        let jobs = costs(&rows, &prices());
+        let order: Vec<(usize, &str)> = (jobs["fib"].iter())
+            .map(|l| (l.size, l.machine.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            [
+                (1, "e2-standard-4"),
+                (10, "e2-standard-4"),
+                (10, "e2-standard-8")
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_out_unpriced_machines() {
+        let rows = [row("e2-standard-4", 10, 1.0), row("laptop", 10, 0.1)];
+        let jobs = costs(&rows, &prices());
+        let machines: Vec<&str> = jobs["fib"].iter().map(|l| l.machine.as_str()).collect();
+        assert_eq!(machines, ["e2-standard-4"]);
+    }
+
+    #[test]
+    fn leaves_out_rows_without_a_machine() {
+        // Without a machine column or label, rows are named after their file
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("results.csv");
+        std::fs::write(
+            &path,
+            "prover,job_name,job_size,proof_duration_millisec,verify_duration_millisec,proof_bytes\n\
+             miden,fib,10,860,1,1024\n",
+        )
+        .unwrap();
+        let rows = load_rows(&Input {
+            machine: None,
+            path,
+        });
+        assert_eq!(rows[0].machine, "results");
+
+        assert!(costs(&rows, &prices()).is_empty());
+        assert_eq!(render(&rows, &prices(), Format::Markdown), "");
+    }
+}
@@ -0,0 +1,226 @@
+// Scaling-law fitting for job-size sweeps.
+//
+// Every model is linear in its two coefficients once the job size (and, for
+// the power law, the measurement) has been transformed, so all of them are
+// solved with the same ordinary least-squares routine. Goodness of fit is
+// always reported as R² over the untransformed measurements, which keeps the
+// numbers comparable across models.
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Model {
+    /// y = a + b·n
+    Linear,
+    /// y = a + b·n·log2(n)
+    NLogN,
+    /// y = a·n^b
+    PowerLaw,
+}
+
+pub const MODELS: [Model; 3] = [Model::Linear, Model::NLogN, Model::PowerLaw];
+
+impl Model {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::NLogN => "n·log n",
+            Self::PowerLaw => "power law",
+        }
+    }
+
+    pub fn formula(&self) -> &'static str {
+        match self {
+            Self::Linear => "a + b·n",
+            Self::NLogN => "a + b·n·log2(n)",
+            Self::PowerLaw => "a·n^b",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Fit {
+    pub model: Model,
+    pub a: f64,
+    pub b: f64,
+    pub r_squared: f64,
+}
+
+impl Fit {
+    pub fn predict(&self, n: f64) -> f64 {
+        match self.model {
+            Model::Linear => self.a + self.b * n,
+            Model::NLogN => self.a + self.b * n * n.log2(),
+            Model::PowerLaw => self.a * n.powf(self.b),
+        }
+    }
+}
+
+/// Least-squares fit of `y = a + b·x`. Returns `None` when there are fewer
+/// than two distinct `x` values.
+fn least_squares(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+
+    if sxx <= f64::EPSILON {
+        return None;
+    }
+
+    let b = sxy / sxx;
+    Some((mean_y - b * mean_x, b))
+}
+
+fn r_squared(fit: &Fit, points: &[(f64, f64)]) -> f64 {
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / points.len() as f64;
+    let ss_tot: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+    let ss_res: f64 = points
+        .iter()
+        .map(|(x, y)| (y - fit.predict(*x)).powi(2))
+        .sum();
+
+    if ss_tot <= f64::EPSILON {
+        // All measurements are equal: the fit is perfect iff it reproduces them.
+        if ss_res <= f64::EPSILON {
+            1.0
+        } else {
+            0.0
+        }
+    } else {
+        1.0 - ss_res / ss_tot
+    }
+}
+
+/// Fit `model` to `(job_size, measurement)` points. Returns `None` when the
+/// points cannot determine the model, e.g. a single job size, or non-positive
+/// values for the power law.
+pub fn fit(model: Model, points: &[(f64, f64)]) -> Option<Fit> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let (a, b) = match model {
+        Model::Linear => least_squares(points)?,
+        Model::NLogN => {
+            if points.iter().any(|(n, _)| *n <= 0.0) {
+                return None;
+            }
+            let transformed: Vec<(f64, f64)> =
+                points.iter().map(|(n, y)| (n * n.log2(), *y)).collect();
+            least_squares(&transformed)?
+        }
+        Model::PowerLaw => {
+            if points.iter().any(|(n, y)| *n <= 0.0 || *y <= 0.0) {
+                return None;
+            }
+            let transformed: Vec<(f64, f64)> =
+                points.iter().map(|(n, y)| (n.ln(), y.ln())).collect();
+            let (ln_a, b) = least_squares(&transformed)?;
+            (ln_a.exp(), b)
+        }
+    };
+
+    let mut fit = Fit {
+        model,
+        a,
+        b,
+        r_squared: 0.0,
+    };
+    fit.r_squared = r_squared(&fit, points);
+    Some(fit)
+}
+
+/// Fit every model that the points can determine.
+pub fn fit_all(points: &[(f64, f64)]) -> Vec<Fit> {
+    MODELS.iter().filter_map(|m| fit(*m, points)).collect()
+}
+
+/// The fit with the highest R², if any.
+pub fn best(fits: &[Fit]) -> Option<&Fit> {
+    fits.iter()
+        .max_by(|f1, f2| f1.r_squared.total_cmp(&f2.r_squared))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZES: [f64; 5] = [1.0, 2.0, 4.0, 8.0, 16.0];
+
+    fn points(f: impl Fn(f64) -> f64) -> Vec<(f64, f64)> {
+        SIZES.iter().map(|&n| (n, f(n))).collect()
+    }
+
+    fn assert_exact(fit: Fit, model: Model, a: f64, b: f64) {
+        assert_eq!(fit.model, model);
+        assert!((fit.a - a).abs() < 1e-9, "a = {}, expected {}", fit.a, a);
+        assert!((fit.b - b).abs() < 1e-9, "b = {}, expected {}", fit.b, b);
+        assert!((fit.r_squared - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn recovers_a_linear_law() {
+        let fit = fit(Model::Linear, &points(|n| 3.0 + 0.5 * n)).unwrap();
+        assert_exact(fit, Model::Linear, 3.0, 0.5);
+        assert!((fit.predict(32.0) - 19.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn recovers_an_n_log_n_law() {
+        let fit = fit(Model::NLogN, &points(|n| 2.0 + 0.25 * n * n.log2())).unwrap();
+        assert_exact(fit, Model::NLogN, 2.0, 0.25);
+    }
+
+    #[test]
+    fn recovers_a_power_law() {
+        let fit = fit(Model::PowerLaw, &points(|n| 1.5 * n.powf(1.3))).unwrap();
+        assert_exact(fit, Model::PowerLaw, 1.5, 1.3);
+    }
+
+    #[test]
+    fn r_squared_is_below_one_for_the_wrong_model() {
+        let fit = fit(Model::Linear, &points(|n| n * n)).unwrap();
+        assert!(fit.r_squared < 1.0);
+    }
+
+    #[test]
+    fn best_picks_the_generating_model() {
+        let fits = fit_all(&points(|n| 2.0 + 0.25 * n * n.log2()));
+        assert_eq!(fits.len(), 3);
+        assert_eq!(best(&fits).unwrap().model, Model::NLogN);
+
+        let fits = fit_all(&points(|n| 4.0 * n.powf(2.5)));
+        assert_eq!(best(&fits).unwrap().model, Model::PowerLaw);
+    }
+
+    #[test]
+    fn best_of_nothing_is_none() {
+        assert!(best(&[]).is_none());
+    }
+
+    #[test]
+    fn needs_two_distinct_sizes() {
+        assert!(fit_all(&[]).is_empty());
+        assert!(fit_all(&[(4.0, 1.0)]).is_empty());
+        assert!(fit_all(&[(4.0, 1.0), (4.0, 2.0), (4.0, 3.0)]).is_empty());
+    }
+
+    #[test]
+    fn power_law_needs_positive_values() {
+        assert!(fit(Model::PowerLaw, &[(1.0, 0.0), (2.0, 1.0)]).is_none());
+        assert!(fit(Model::PowerLaw, &[(1.0, -1.0), (2.0, 1.0)]).is_none());
+        assert!(fit(Model::PowerLaw, &[(0.0, 1.0), (2.0, 1.0)]).is_none());
+        assert!(fit(Model::NLogN, &[(0.0, 1.0), (2.0, 1.0)]).is_none());
+        assert!(fit(Model::Linear, &[(0.0, -1.0), (2.0, 1.0)]).is_some());
+    }
+
+    #[test]
+    fn constant_measurements_fit_perfectly() {
+        let fit = fit(Model::Linear, &points(|_| 7.0)).unwrap();
+        assert_exact(fit, Model::Linear, 7.0, 0.0);
+    }
+}
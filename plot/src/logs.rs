@@ -0,0 +1,290 @@
+// Results from the logs uploaded by the GCP batch instances, such as
+// `docker/example-output/test-batch-instance-e2-standard-4.log`. They hold
+// only the `Metrics::println` block of every job, between the section
+// headers the old `all.sh` printed for each system:
+//
+//     Start: Polygon Miden
+//     + begin job_number:   0 iter_blake3
+//     + job_name:           "iter_blake3"
+//     + job_size:           1
+//     + proof_duration:     859.5634ms
+//     + verify_duration:    951.626µs
+//     ...
+//     + end job_number:     0
+
+use crate::results::{Input, Row};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+// Section headers of the old `all.sh`, and the prover of each system
+const SYSTEMS: [(&str, &str); 2] = [("Polygon Miden", "miden"), ("RISC Zero", "risczero")];
+
+/// Milliseconds in a `Duration` debug string, such as `7.362906171s` or
+/// `951.626µs`.
+pub fn parse_millis(s: &str) -> Option<f32> {
+    // Longest suffixes first, as every unit ends in `s`
+    let units = [
+        ("ns", 1e-6),
+        ("µs", 1e-3),
+        ("us", 1e-3),
+        ("ms", 1.0),
+        ("s", 1e3),
+    ];
+    let (value, factor) = units
+        .iter()
+        .find_map(|(unit, factor)| Some((s.strip_suffix(unit)?, factor)))?;
+    Some(value.parse::<f32>().ok()? * factor)
+}
+
+/// The GCP machine type in an instance name, which the launch scripts form
+/// as `${INSTANCE_NAME_BASE}-${MACHINE_TYPE}`: the name from its first part
+/// that looks like a machine family, such as `e2` or `n2d`.
+pub fn machine_type(instance: &str) -> Option<&str> {
+    let is_family = |part: &str| {
+        let letters = part.trim_end_matches(|c: char| c.is_ascii_lowercase());
+        let digits = letters.trim_start_matches(|c: char| c.is_ascii_lowercase());
+        part.len() <= 4
+            && digits.len() < letters.len()
+            && !digits.is_empty()
+            && digits.chars().all(|c| c.is_ascii_digit())
+    };
+    let mut start = 0;
+    for part in instance.split('-') {
+        if is_family(part) && start + part.len() < instance.len() {
+            return Some(&instance[start..]);
+        }
+        start += part.len() + 1;
+    }
+    None
+}
+
+fn field<'a>(fields: &HashMap<&str, &'a str>, key: &str, path: &Path, job: &str) -> &'a str {
+    fields
+        .get(key)
+        .unwrap_or_else(|| panic!("No {} for job {} in {}", key, job, path.display()))
+}
+
+/// Every job of a log, tagged with the machine type of the instance that
+/// wrote it unless `input` is labelled with a machine. Jobs cut off by the
+/// end of the log are left out.
+pub fn load_rows(input: &Input) -> Vec<Row> {
+    let path = &input.path;
+    let log = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let machine = input
+        .machine
+        .clone()
+        .or_else(|| machine_type(&stem).map(String::from))
+        .unwrap_or(stem);
+
+    let mut rows = Vec::new();
+    let mut prover: Option<String> = None;
+    let mut job: Option<(&str, HashMap<&str, &str>)> = None;
+    for line in log.lines() {
+        if let Some(system) = line.strip_prefix("Start: ") {
+            let system = system.trim();
+            prover = Some(String::from(
+                SYSTEMS
+                    .iter()
+                    .find(|(name, _)| *name == system)
+                    .map_or(system, |(_, prover)| prover),
+            ));
+            continue;
+        }
+        let Some((key, value)) = line
+            .strip_prefix("+ ")
+            .and_then(|l| l.split_once(':'))
+            .map(|(k, v)| (k.trim(), v.trim()))
+        else {
+            continue;
+        };
+
+        match key {
+            "begin job_number" => job = Some((value, HashMap::new())),
+            "end job_number" => {
+                let Some((number, fields)) = job.take() else {
+                    continue;
+                };
+                let get = |key| field(&fields, key, path, number);
+                let duration = |key| {
+                    parse_millis(get(key)).unwrap_or_else(|| {
+                        panic!("Invalid {} for job {} in {}", key, number, path.display())
+                    })
+                };
+                // Logs of newer hosts name the prover of every job
+                let prover = match fields.get("prover") {
+                    Some(p) => String::from(p.trim_matches('"')),
+                    None => prover.clone().unwrap_or_else(|| {
+                        panic!(
+                            "No system started before job {} in {}",
+                            number,
+                            path.display()
+                        )
+                    }),
+                };
+                rows.push(Row {
+                    source: path.clone(),
+                    machine: machine.clone(),
+                    prover,
+                    job_name: String::from(get("job_name").trim_matches('"')),
+                    job_size: get("job_size").parse().unwrap(),
+                    proof_secs: duration("proof_duration") / 1_000.0,
+                    verify_millis: duration("verify_duration"),
+                    proof_bytes: get("proof_bytes").parse().unwrap(),
+                    security_bits: fields.get("security_bits").and_then(|b| b.parse().ok()),
+                    // Logs do not record thread counts
+                    threads: None,
+                });
+            }
+            _ => {
+                if let Some((_, fields)) = &mut job {
+                    fields.insert(key, value);
+                }
+            }
+        }
+    }
+    rows
+}
+
+// The columns of the results CSV that logs have values for. Durations keep
+// their fractions, so that sub-millisecond verification stays visible.
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    prover: &'a str,
+    job_name: &'a str,
+    job_size: usize,
+    proof_duration_millisec: f32,
+    verify_duration_millisec: f32,
+    proof_bytes: u64,
+    security_bits: Option<u32>,
+    machine: &'a str,
+}
+
+/// Writes `rows` in the schema of results CSV files.
+pub fn write_rows<W: std::io::Write>(writer: &mut csv::Writer<W>, rows: &[&Row]) {
+    for row in rows {
+        writer
+            .serialize(CsvRow {
+                prover: &row.prover,
+                job_name: &row.job_name,
+                job_size: row.job_size,
+                proof_duration_millisec: row.proof_secs * 1_000.0,
+                verify_duration_millisec: row.verify_millis,
+                proof_bytes: row.proof_bytes as u64,
+                security_bits: row.security_bits,
+                machine: &row.machine,
+            })
+            .unwrap();
+    }
+}
+
+/// Writes the jobs of every log as one results CSV, to `out` or to stdout.
+pub fn import(inputs: &[Input], out: Option<&Path>) {
+    let writer: Box<dyn std::io::Write> = match out {
+        Some(path) => Box::new(
+            std::fs::File::create(path)
+                .unwrap_or_else(|e| panic!("Could not create {}: {}", path.display(), e)),
+        ),
+        None => Box::new(std::io::stdout()),
+    };
+    let mut writer = csv::Writer::from_writer(writer);
+    for input in inputs {
+        let rows = load_rows(input);
+        if rows.is_empty() {
+            eprintln!("No jobs in {}", input.path.display());
+        }
+        write_rows(&mut writer, &rows.iter().collect::<Vec<_>>());
+    }
+    writer.flush().unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+Start: Polygon Miden
++ begin job_number:   0 iter_blake3
++ job_name:           \"iter_blake3\"
++ job_size:           10
++ proof_duration:     7.362906171s
++ verify_duration:    951.626µs
++ proof_bytes:        82523
++ end job_number:     0
+
++ begin job_number:   1 iter_blake3
++ job_name:           \"iter_blake3\"
+";
+
+    fn assert_millis(s: &str, millis: f32) {
+        let parsed = parse_millis(s).unwrap();
+        assert!(
+            (parsed - millis).abs() <= millis * 1e-6,
+            "{} parsed as {} ms, expected {}",
+            s,
+            parsed,
+            millis
+        );
+    }
+
+    #[test]
+    fn parses_durations_in_every_unit() {
+        assert_millis("7.362906171s", 7362.906);
+        assert_millis("951.626µs", 0.951626);
+        assert_millis("951.626us", 0.951626);
+        assert_millis("859.5634ms", 859.5634);
+        assert_millis("1500ns", 0.0015);
+    }
+
+    #[test]
+    fn rejects_malformed_durations() {
+        for s in ["", "s", "12", "1.5h", "ms", "1.2.3s", "7 s", "fast"] {
+            assert_eq!(parse_millis(s), None, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn finds_the_machine_type_in_instance_names() {
+        assert_eq!(
+            machine_type("test-batch-instance-e2-standard-4"),
+            Some("e2-standard-4")
+        );
+        assert_eq!(machine_type("bench-n2d-highcpu-16"), Some("n2d-highcpu-16"));
+        assert_eq!(machine_type("e2-highmem-2"), Some("e2-highmem-2"));
+        assert_eq!(machine_type("test-batch-instance"), None);
+        assert_eq!(machine_type("instance-e2"), None);
+    }
+
+    fn rows(label: &str) -> Vec<Row> {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test-batch-instance-e2-standard-4.log");
+        std::fs::write(&path, LOG).unwrap();
+        let input: Input = format!("{}{}", label, path.display()).parse().unwrap();
+        load_rows(&input)
+    }
+
+    #[test]
+    fn loads_complete_jobs_of_a_log() {
+        let rows = rows("");
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row.machine, "e2-standard-4");
+        assert_eq!(row.prover, "miden");
+        assert_eq!((row.job_name.as_str(), row.job_size), ("iter_blake3", 10));
+        assert!((row.proof_secs - 7.362906).abs() < 1e-5);
+        assert!((row.verify_millis - 0.951626).abs() < 1e-6);
+        assert_eq!(row.proof_bytes, 82523.0);
+        assert_eq!(row.security_bits, None);
+    }
+
+    #[test]
+    fn machine_label_overrides_the_instance_name() {
+        let rows = rows("workstation=");
+        assert_eq!(rows[0].machine, "workstation");
+    }
+}
@@ -1,216 +1,374 @@
-use plotters::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use clap::{Parser, Subcommand};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+mod charts;
+mod costs;
+mod fit;
+mod logs;
+mod prices;
+mod report;
+mod results;
+mod segments;
+mod tables;
+mod terminal;
+
+use charts::PlotOptions;
+use results::{Inputs, Row};
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(flatten)]
+    inputs: Inputs,
+
+    #[command(flatten)]
+    plot: PlotOptions,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
 
-type Table = HashMap<(String, String), Vec<(usize, f32)>>;
+#[derive(Subcommand)]
+enum Command {
+    /// Draw one chart per metric and job (default)
+    Plot {
+        #[command(flatten)]
+        inputs: Inputs,
+
+        #[command(flatten)]
+        options: PlotOptions,
+    },
+    /// Fit scaling laws of every metric against job size
+    Fit {
+        #[command(flatten)]
+        inputs: Inputs,
+
+        /// Job sizes for which to report predicted values
+        #[arg(long, value_name = "SIZE")]
+        predict: Vec<f64>,
+    },
+    /// Render the README comparison tables
+    Tables {
+        #[command(flatten)]
+        inputs: Inputs,
+
+        #[arg(long, value_enum, default_value_t = tables::Format::Html)]
+        format: tables::Format,
+    },
+    /// Write a static HTML report with the charts, summary tables, raw data
+    /// and environment of every job into the output directory
+    Report {
+        #[command(flatten)]
+        inputs: Inputs,
+
+        #[command(flatten)]
+        options: PlotOptions,
+
+        /// Write a single HTML file with the charts and raw data embedded,
+        /// instead of keeping them in files beside it
+        #[arg(long)]
+        single_file: bool,
+    },
+    /// Render what proofs cost per job, system and machine, from a price
+    /// table of the machines
+    Cost {
+        #[command(flatten)]
+        inputs: Inputs,
+
+        /// TOML table of machine prices in US dollars per hour, such as
+        /// `e2-standard-4 = 0.134`
+        #[arg(long, value_name = "FILE")]
+        prices: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = tables::Format::Markdown)]
+        format: tables::Format,
+    },
+    /// Print tables and text charts of the medians of every metric and job,
+    /// for terminals without a display
+    Show {
+        #[command(flatten)]
+        inputs: Inputs,
+
+        #[command(flatten)]
+        options: terminal::ShowOptions,
+    },
+    /// Convert the logs of GCP batch instances into a results CSV, tagged
+    /// with the machine type in each instance's name
+    Import {
+        // Logs such as test-batch-instance-e2-standard-4.log
+        #[arg(value_name = "[MACHINE=]LOG", required = true)]
+        logs: Vec<results::Input>,
+
+        /// File to write the results to, instead of stdout
+        #[arg(long, short, value_name = "FILE")]
+        out: Option<PathBuf>,
+    },
+    /// Break down RISC Zero proofs by segment, from the JSON output
+    Segments {
+        // JSON lines files written next to the CSV results
+        #[arg(value_name = "FILE", required = true)]
+        files: Vec<PathBuf>,
+
+        #[command(flatten)]
+        options: PlotOptions,
+    },
+}
 
-fn load_csv() -> (Table, Table, Table) {
-    let argv: Vec<String> = std::env::args().collect();
+/// Samples of every job and series, sorted by job size.
+type Table = BTreeMap<(String, String), Vec<(usize, f32)>>;
 
-    if argv.len() < 2 {
-        panic!("Not enough arguments")
+// A series holds the samples of one prover on one machine with one thread
+// count. It is named after the prover, followed by the machine and thread
+// count when the results hold several of them.
+fn series(row: &Row, machines: bool, threads: bool) -> String {
+    let mut config = Vec::new();
+    if machines {
+        config.push(row.machine.clone());
     }
+    if threads {
+        config.push(match row.threads {
+            Some(threads) => format!("{} threads", threads),
+            None => String::from("default threads"),
+        });
+    }
+    match config.is_empty() {
+        true => row.prover.clone(),
+        false => format!("{} ({})", row.prover, config.join(", ")),
+    }
+}
 
-    let path = Path::new(&argv[1]);
-
-    let mut rdr = csv::Reader::from_path(path).unwrap();
-
-    let mut index: HashMap<&str, usize> = HashMap::new();
+fn load_csv(inputs: &Inputs) -> (Table, Table, Table) {
+    let mut proof_durations = BTreeMap::new();
+    let mut verify_durations = BTreeMap::new();
+    let mut proof_sizes = BTreeMap::new();
 
-    for (c, hdr) in rdr.headers().unwrap().into_iter().enumerate() {
-        index.insert(hdr, c);
-    }
+    let rows = results::load_all(inputs);
+    let machines = rows.iter().map(|r| &r.machine).collect::<BTreeSet<_>>();
+    let threads = rows.iter().map(|r| r.threads).collect::<BTreeSet<_>>();
+    let (machines, threads) = (machines.len() > 1, threads.len() > 1);
 
-    let mut rdr = csv::Reader::from_path(path).unwrap();
+    rows.into_iter().for_each(|row| {
+        let key = (row.job_name.clone(), series(&row, machines, threads));
 
-    let data: Vec<Vec<String>> = rdr
-        .records()
-        .filter_map(|row| if let Ok(row) = row { Some(row) } else { None })
-        .map(|row| {
-            let mut vec = Vec::new();
-            for e in row.into_iter() {
-                vec.push(String::from(e))
-            }
-            vec
-        })
-        .collect();
-
-    let mut proof_durations = HashMap::new();
-    let mut verify_durations = HashMap::new();
-    let mut proof_sizes = HashMap::new();
-
-    data.into_iter().for_each(|row| {
-        let job_size = row[index["job_size"]].parse::<usize>().unwrap();
-        let key = (row[index["job_name"]].clone(), row[index["prover"]].clone());
-        let proof_duration = row[index["proof_duration_millisec"]]
-            .parse::<f32>()
-            .unwrap();
-        let verify_duration = row[index["verify_duration_millisec"]]
-            .parse::<f32>()
-            .unwrap();
-        let proof_size = row[index["proof_bytes"]].parse::<f32>().unwrap();
-
-        // cast milliseconds to seconds for proof duration
         proof_durations
             .entry(key.clone())
             .or_insert(Vec::new())
-            .push((job_size, proof_duration / 1_000.0));
+            .push((row.job_size, row.proof_secs));
         verify_durations
             .entry(key.clone())
             .or_insert(Vec::new())
-            .push((job_size, verify_duration));
+            .push((row.job_size, row.verify_millis));
         proof_sizes
             .entry(key)
             .or_insert(Vec::new())
-            .push((job_size, proof_size));
+            .push((row.job_size, row.proof_bytes));
     });
 
-    proof_durations
-        .values_mut()
-        .for_each(|v| v.sort_by(|(s1, _), (s2, _)| s1.cmp(s2)));
+    for table in [
+        &mut proof_durations,
+        &mut verify_durations,
+        &mut proof_sizes,
+    ] {
+        table
+            .values_mut()
+            .for_each(|v: &mut Vec<(usize, f32)>| v.sort_by_key(|(s, _)| *s));
+    }
 
     (proof_durations, verify_durations, proof_sizes)
 }
 
-const PROVERS: [&str; 3] = ["Bonsai", "CpuSHA256", "CpuPoseidon"];
-
-fn plot_data(filename: &str, title: &str, data: &Vec<Vec<(usize, f32)>>) {
-    let root_area = BitMapBackend::new(filename, (1024, 768)).into_drawing_area();
-
-    root_area.fill(&WHITE).unwrap();
-
-    let root_area = root_area.titled(title, ("sans-serif", 60)).unwrap();
-
-    let (xmin, xmax) = data
-        .iter()
-        .flatten()
-        .map(|(x, _y)| *x as f32)
-        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), x| {
-            (min.min(x), max.max(x))
-        });
-
-    let (_ymin, ymax) = data
-        .iter()
-        .flatten()
-        .map(|(_x, y)| *y)
-        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), y| {
-            (min.min(y), max.max(y))
-        });
-
-    let xrange = xmin..xmax;
-    let yrange = 0.0..(ymax * 1.5);
-
-    let mut cc = ChartBuilder::on(&root_area)
-        .margin(5)
-        .set_all_label_area_size(50)
-        // .caption("Sine and Cosine", ("sans-serif", 40))
-        .build_cartesian_2d(xrange, yrange)
-        .unwrap();
-
-    cc.configure_mesh()
-        .x_labels(20)
-        .y_labels(10)
-        .disable_mesh()
-        .x_label_formatter(&|v| format!("{:.1}", v))
-        .y_label_formatter(&|v| format!("{:.1}", v))
-        .draw()
-        .unwrap();
-
-    data.iter().enumerate().for_each(|(index, v)| {
-        let prover = PROVERS[index];
-        let points = v.iter().map(|(s, x)| (*s as f32, *x));
-        let series = LineSeries::new(points, &Palette99::pick(index));
-        cc.draw_series(series)
-            .unwrap()
-            .label(prover)
-            .legend(move |(x, y)| {
-                PathElement::new(vec![(x, y), (x + 20, y)], Palette99::pick(index))
-            });
-    });
-
-    cc.configure_series_labels()
-        .border_style(BLACK)
-        .draw()
-        .unwrap();
-
-    // data.iter().for_each(|v| {
-    //     let points = v.iter().map(|(s, x)| (*s as f32, *x));
-    //     let series = LineSeries::new(points, &RED);
-    //     cc.draw_series(series).unwrap();
-    // });
-
-    root_area.present().expect("Unable to write result to file");
-}
-
-fn display() {
-    // Plotting
-    let (proof_durations, verify_durations, proof_sizes) = load_csv();
+fn fit_report(inputs: &Inputs, predict: &[f64]) {
+    let (proof_durations, verify_durations, proof_sizes) = load_csv(inputs);
 
     let all_data = [
-        (&proof_durations, "proving_time"),
-        (&verify_durations, "verifying_time"),
-        (&proof_sizes, "proof_size"),
+        (&proof_durations, "proving_time", "s"),
+        (&verify_durations, "verifying_time", "ms"),
+        (&proof_sizes, "proof_size", "bytes"),
     ];
 
-    for (hmap, name) in all_data {
-        let mut jobs = HashSet::new();
-        hmap.keys().for_each(|(job_name, _)| {
-            let _ = jobs.insert(job_name);
-        });
-
-        for j in jobs {
-            // proving time
-            let mut data = Vec::new();
-
-            for prover in PROVERS {
-                let k = &(j.clone(), String::from(prover));
-                data.push(hmap[k].clone());
+    for (hmap, name, unit) in all_data {
+        let mut keys: Vec<_> = hmap.keys().collect();
+        keys.sort();
+
+        for k in keys {
+            let (job, series) = k;
+            let points: Vec<(f64, f64)> = hmap[k]
+                .iter()
+                .map(|(s, y)| (*s as f64, *y as f64))
+                .collect();
+
+            println!(
+                "{} {} {} ({} points, {})",
+                name,
+                job,
+                series,
+                points.len(),
+                unit
+            );
+
+            let fits = fit::fit_all(&points);
+            if fits.is_empty() {
+                println!("    not enough distinct job sizes to fit");
+                continue;
             }
 
-            let filename = format!("{}_{}.png", name, j);
-            let title = format!("{} {}", name, j);
-
-            plot_data(&filename, &title, &data)
+            let best = fit::best(&fits).map(|f| f.model);
+            for f in &fits {
+                let marker = if Some(f.model) == best { "*" } else { " " };
+                println!(
+                    "  {} {:<10} {:<16} a = {:<12.6e} b = {:<12.6e} R² = {:.4}",
+                    marker,
+                    f.model.name(),
+                    f.model.formula(),
+                    f.a,
+                    f.b,
+                    f.r_squared
+                );
+                for n in predict {
+                    println!(
+                        "        predicted at n = {}: {:.3} {}",
+                        n,
+                        f.predict(*n),
+                        unit
+                    );
+                }
+            }
         }
     }
+}
 
-    // for prover in PROVERS {
-    //     let data = jobs.iter ().map (|job|
-    //                                  {
-    //                                      let k = (job_name, String::from(prover));
-    //                                      let data =
-    //                                  }
-    //     )
-    // }
-
-    // proof_durations.into_iter().for_each(|(k, v)| {
-    //     let (job_name, prover) = k;
-
-    //     let filename = format!("proving_time_{}_{}.png", job_name, prover);
-    //     let title = format!("proving time {} {}", job_name, prover);
-
-    //     plot_data(&filename, &title, &v)
-    // });
-
-    // verify_durations.into_iter().for_each(|(k, v)| {
-    //     let (job_name, prover) = k;
-
-    //     let filename = format!("verifying_time_{}_{}.png", job_name, prover);
-    //     let title = format!("verifying time {} {}", job_name, prover);
-
-    //     plot_data(&filename, &title, &v)
-    // });
+fn main() {
+    let cli = Cli::parse();
+
+    let default = Command::Plot {
+        inputs: cli.inputs,
+        options: cli.plot,
+    };
+    match cli.command.unwrap_or(default) {
+        Command::Plot { inputs, options } => charts::plot_all(
+            &results::load_all(&inputs),
+            &results::load_memory(&inputs),
+            &options,
+        ),
+        Command::Fit { inputs, predict } => fit_report(&inputs, &predict),
+        Command::Tables { inputs, format } => {
+            let rows = results::load_all(&inputs);
+            print!("{}", tables::render(&rows, format))
+        }
+        Command::Report {
+            inputs,
+            options,
+            single_file,
+        } => report::write(&inputs, &options, single_file),
+        Command::Cost {
+            inputs,
+            prices,
+            format,
+        } => {
+            let rows = results::load_all(&inputs);
+            let prices = prices::Prices::load(&prices);
+            print!("{}", costs::render(&rows, &prices, format))
+        }
+        Command::Show { inputs, options } => {
+            let (proof_durations, verify_durations, proof_sizes) = load_csv(&inputs);
+            print!(
+                "{}",
+                terminal::render(&proof_durations, &verify_durations, &proof_sizes, &options)
+            )
+        }
+        Command::Import { logs, out } => logs::import(&logs, out.as_deref()),
+        Command::Segments { files, options } => segments::report(&files, &options),
+    }
+}
 
-    // proof_sizes.into_iter().for_each(|(k, v)| {
-    //     let (job_name, prover) = k;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Results of one prover on two machines. Proving takes twice as long per
+    // unit of work on the smaller one.
+    fn two_machines(dir: &std::path::Path) -> Inputs {
+        let path = dir.join("results.csv");
+        let mut csv = String::from(
+            "prover,job_name,job_size,proof_duration_millisec,verify_duration_millisec,proof_bytes,machine\n",
+        );
+        for size in [1, 2, 4, 8] {
+            csv += &format!("miden,fib,{},{},1,1024,e2-highmem-2\n", size, 2000 * size);
+            csv += &format!("miden,fib,{},{},1,1024,e2-standard-8\n", size, 1000 * size);
+        }
+        std::fs::write(&path, csv).unwrap();
+        Inputs {
+            inputs: vec![path.display().to_string().parse().unwrap()],
+            allow_mixed_security: false,
+        }
+    }
 
-    //     let filename = format!("proof_size_{}_{}.png", job_name, prover);
-    //     let title = format!("proof size {} {}", job_name, prover);
+    #[test]
+    fn fits_each_machine_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let (proof_durations, _, _) = load_csv(&two_machines(dir.path()));
+        let slopes: Vec<(&str, f64)> = (proof_durations.iter())
+            .map(|((_, series), samples)| {
+                let points: Vec<(f64, f64)> = (samples.iter())
+                    .map(|(n, y)| (*n as f64, *y as f64))
+                    .collect();
+                let fit = fit::fit(fit::Model::Linear, &points).unwrap();
+                (series.as_str(), fit.b)
+            })
+            .collect();
+
+        assert_eq!(slopes.len(), 2);
+        assert_eq!(slopes[0].0, "miden (e2-highmem-2)");
+        assert_eq!(slopes[1].0, "miden (e2-standard-8)");
+        assert!((slopes[0].1 - 2.0).abs() < 1e-6);
+        assert!((slopes[1].1 - 1.0).abs() < 1e-6);
+    }
 
-    //     plot_data(&filename, &title, &v)
-    // });
-}
+    #[test]
+    fn shows_each_machine_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let (proof_durations, verify_durations, proof_sizes) = load_csv(&two_machines(dir.path()));
+        let options = terminal::ShowOptions {
+            jobs: Vec::new(),
+            width: 20,
+            height: 4,
+            ascii: true,
+            no_charts: true,
+        };
+
+        let out = terminal::render(&proof_durations, &verify_durations, &proof_sizes, &options);
+        let table: Vec<&str> = out.lines().skip(2).take(3).collect();
+        assert_eq!(
+            table,
+            [
+                "prover                     1      2      4      8",
+                "miden (e2-highmem-2)   2.000  4.000  8.000  16.00",
+                "miden (e2-standard-8)  1.000  2.000  4.000  8.000",
+            ]
+        );
+    }
 
-fn main() {
-    display()
+    #[test]
+    fn names_thread_counts_only_when_they_differ() {
+        let row = |threads| Row {
+            source: PathBuf::from("results.csv"),
+            machine: String::from("e2-standard-4"),
+            prover: String::from("miden"),
+            job_name: String::from("fib"),
+            job_size: 1,
+            proof_secs: 1.0,
+            verify_millis: 1.0,
+            proof_bytes: 1.0,
+            security_bits: None,
+            threads,
+        };
+        assert_eq!(series(&row(Some(4)), false, false), "miden");
+        assert_eq!(series(&row(Some(4)), false, true), "miden (4 threads)");
+        assert_eq!(
+            series(&row(None), true, true),
+            "miden (e2-standard-4, default threads)"
+        );
+    }
 }
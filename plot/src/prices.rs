@@ -0,0 +1,31 @@
+// Hourly prices of the machines results were produced on, to turn durations
+// into costs. Prices are read from a TOML table of US dollars per hour, keyed
+// by machine type as results are tagged:
+//
+//     e2-standard-4 = 0.134
+//     e2-highmem-4 = 0.181
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+pub struct Prices(BTreeMap<String, f64>);
+
+impl Prices {
+    pub fn load(path: &Path) -> Self {
+        let text = std::fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+        let prices: BTreeMap<String, f64> = toml::from_str(&text)
+            .unwrap_or_else(|e| panic!("Invalid price table {}: {}", path.display(), e));
+        Prices(prices)
+    }
+
+    /// US dollars per hour of `machine`, if priced.
+    pub fn hourly(&self, machine: &str) -> Option<f64> {
+        self.0.get(machine).copied()
+    }
+
+    /// US dollars spent on `machine` over `secs`, if it is priced.
+    pub fn cost(&self, machine: &str, secs: f64) -> Option<f64> {
+        Some(self.hourly(machine)? * secs / 3600.0)
+    }
+}
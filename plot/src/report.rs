@@ -0,0 +1,466 @@
+// A static HTML report of the results, to attach to issues or archive next to
+// a run under data/. It has a section per job with links to its raw data, a
+// sortable summary table and its charts, after the environment recorded in
+// the `manifest.json` of each run. Styles and scripts are inline and nothing
+// is fetched, so the report works offline.
+//
+// As a directory, the report is `report.html` beside `charts/` and `data/`.
+// As a single file, charts are inlined and raw data embedded as data URIs.
+
+use crate::charts::{self, PlotOptions, Svg};
+use crate::logs;
+use crate::results::{self, median, Inputs, Row};
+use crate::tables::system_name;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em auto; max-width: 1100px; padding: 0 1em; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.6em; text-align: right; }
+th { background: #f4f4f4; }
+td.text, th.text { text-align: left; }
+table.sortable th { cursor: pointer; user-select: none; }
+table.sortable th[data-order=ascending]::after { content: \" \\25B2\"; }
+table.sortable th[data-order=descending]::after { content: \" \\25BC\"; }
+.chart img, .chart svg { max-width: 100%; height: auto; }
+";
+
+// Sorts a table by the clicked column, numerically where both cells are
+// numbers, and reverses the order on a second click
+const SCRIPT: &str = "
+document.querySelectorAll('table.sortable th').forEach((th, column) => {
+  th.addEventListener('click', () => {
+    const body = th.closest('table').tBodies[0];
+    const ascending = th.dataset.order !== 'ascending';
+    th.parentElement.querySelectorAll('th').forEach(h => delete h.dataset.order);
+    th.dataset.order = ascending ? 'ascending' : 'descending';
+    const key = row => row.cells[column].textContent.trim();
+    const rows = Array.from(body.rows).sort((a, b) => {
+      const [x, y] = [key(a), key(b)];
+      const order = x !== '' && y !== '' && !isNaN(x) && !isNaN(y) ? x - y : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    body.append(...rows);
+  });
+});
+";
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn data_uri(csv: &[u8]) -> String {
+    let mut uri = String::from("data:text/csv;charset=utf-8,");
+    for &b in csv {
+        if b.is_ascii_alphanumeric() || b"-_.,".contains(&b) {
+            uri.push(b as char);
+        } else {
+            write!(uri, "%{:02X}", b).unwrap();
+        }
+    }
+    uri
+}
+
+// Records of `job` in a results CSV, verbatim under the file's header
+fn extract(path: &Path, job: &str) -> Vec<u8> {
+    let mut reader = csv::Reader::from_path(path)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+    let headers = reader.headers().unwrap().clone();
+    let column = headers.iter().position(|h| h == "job_name").unwrap();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(&headers).unwrap();
+    for record in reader.records().filter_map(|r| r.ok()) {
+        if &record[column] == job {
+            writer.write_record(&record).unwrap();
+        }
+    }
+    writer.into_inner().unwrap()
+}
+
+// Scalar fields of a manifest, nested ones as `parent.field`
+fn fields(prefix: &str, value: &Value, out: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = match prefix {
+                    "" => key.clone(),
+                    prefix => format!("{}.{}", prefix, key),
+                };
+                fields(&key, value, out);
+            }
+        }
+        Value::Null | Value::Array(_) => {}
+        Value::String(s) => out.push((String::from(prefix), s.clone())),
+        other => out.push((String::from(prefix), other.to_string())),
+    }
+}
+
+fn render_environment(out: &mut String, inputs: &Inputs) {
+    writeln!(out, "<h2 id=\"environment\">Environment</h2>").unwrap();
+    let manifests: BTreeSet<PathBuf> = (inputs.inputs.iter())
+        .map(|i| i.path.with_file_name("manifest.json"))
+        .filter(|p| p.exists())
+        .collect();
+    if manifests.is_empty() {
+        writeln!(
+            out,
+            "<p>No <code>manifest.json</code> next to the results; the environment was not recorded.</p>"
+        )
+        .unwrap();
+    }
+
+    for path in manifests {
+        let text = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+        let manifest: Value = serde_json::from_str(&text)
+            .unwrap_or_else(|e| panic!("Invalid manifest {}: {}", path.display(), e));
+        writeln!(out, "<h3>{}</h3>", escape(&path.display().to_string())).unwrap();
+
+        let mut scalars = Vec::new();
+        fields("", &manifest, &mut scalars);
+        writeln!(out, "<table>").unwrap();
+        for (key, value) in scalars {
+            writeln!(
+                out,
+                "<tr><th class=\"text\">{}</th><td class=\"text\">{}</td></tr>",
+                escape(&key),
+                escape(&value)
+            )
+            .unwrap();
+        }
+        writeln!(out, "</table>").unwrap();
+
+        let Some(systems) = manifest["systems"].as_array() else {
+            continue;
+        };
+        writeln!(out, "<table>").unwrap();
+        writeln!(
+            out,
+            "<tr><th class=\"text\">System</th><th class=\"text\">Toolchain</th><th>Build</th><th>Benches passed</th></tr>"
+        )
+        .unwrap();
+        for system in systems {
+            let benches = system["benches"].as_array().map_or(&[][..], |b| b);
+            // Benches the suite left out were never run
+            let (skipped, run): (Vec<&Value>, Vec<&Value>) =
+                (benches.iter()).partition(|b| b["skipped"].as_bool() == Some(true));
+            let passed = (run.iter())
+                .filter(|b| b["success"].as_bool() == Some(true))
+                .count();
+            let skipped = match skipped.len() {
+                0 => String::new(),
+                n => format!(", {} skipped", n),
+            };
+            writeln!(
+                out,
+                "<tr><td class=\"text\">{}</td><td class=\"text\">{}</td><td>{}</td><td>{} of {}{}</td></tr>",
+                escape(system["name"].as_str().unwrap_or_default()),
+                escape(system["rustc"].as_str().unwrap_or_default()),
+                match system["build"]["success"].as_bool() {
+                    Some(true) => "ok",
+                    _ => "failed",
+                },
+                passed,
+                run.len(),
+                skipped
+            )
+            .unwrap();
+        }
+        writeln!(out, "</table>").unwrap();
+    }
+}
+
+// Links to the records of `job` in every file they came from
+fn render_raw_data(out: &mut String, job: &str, rows: &[&Row], dir: Option<&Path>) {
+    let sources: BTreeSet<&PathBuf> = rows.iter().map(|r| &r.source).collect();
+    write!(out, "<p>Raw data:").unwrap();
+    for (index, source) in sources.into_iter().enumerate() {
+        let csv = if source.extension().is_some_and(|e| e == "log") {
+            let from_log: Vec<&Row> = rows
+                .iter()
+                .copied()
+                .filter(|r| r.source == *source)
+                .collect();
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            logs::write_rows(&mut writer, &from_log);
+            writer.into_inner().unwrap()
+        } else {
+            extract(source, job)
+        };
+        let name = format!("{}_{}.csv", job, index);
+        let href = match dir {
+            Some(dir) => {
+                let path = dir.join("data").join(&name);
+                std::fs::write(&path, csv)
+                    .unwrap_or_else(|e| panic!("Could not write {}: {}", path.display(), e));
+                format!("data/{}", name)
+            }
+            None => data_uri(&csv),
+        };
+        write!(
+            out,
+            " <a download=\"{}\" href=\"{}\">{}</a>",
+            escape(&name),
+            escape(&href),
+            escape(&source.display().to_string())
+        )
+        .unwrap();
+    }
+    writeln!(out, "</p>").unwrap();
+}
+
+// One line per machine, prover, job size and security level, as results at
+// different levels are not comparable
+fn render_summary(out: &mut String, rows: &[&Row]) {
+    type Key<'a> = (&'a str, &'a str, usize, Option<u32>);
+    let mut groups: BTreeMap<Key, Vec<&Row>> = BTreeMap::new();
+    for row in rows {
+        (groups
+            .entry((&row.machine, &row.prover, row.job_size, row.security_bits))
+            .or_default())
+        .push(row);
+    }
+
+    writeln!(out, "<table class=\"sortable\">").unwrap();
+    writeln!(out, "<thead><tr>").unwrap();
+    for (header, class) in [
+        ("Machine", " class=\"text\""),
+        ("System", " class=\"text\""),
+        ("Prover", " class=\"text\""),
+        ("Job size", ""),
+        ("Runs", ""),
+        ("Prover time (sec)", ""),
+        ("Verifier time (ms)", ""),
+        ("Proof size (KB)", ""),
+        ("Security bits", ""),
+    ] {
+        write!(out, "<th{}>{}</th>", class, header).unwrap();
+    }
+    writeln!(out, "</tr></thead>").unwrap();
+
+    writeln!(out, "<tbody>").unwrap();
+    for ((machine, prover, size, bits), rows) in groups {
+        let medians = |value: fn(&Row) -> f32| {
+            let mut values: Vec<f32> = rows.iter().map(|r| value(r)).collect();
+            median(&mut values)
+        };
+        writeln!(
+            out,
+            "<tr><td class=\"text\">{}</td><td class=\"text\">{}</td><td class=\"text\">{}</td><td>{}</td><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.1}</td><td>{}</td></tr>",
+            escape(machine),
+            escape(system_name(prover)),
+            escape(prover),
+            size,
+            rows.len(),
+            medians(|r| r.proof_secs),
+            medians(|r| r.verify_millis),
+            medians(|r| r.proof_bytes / 1024.0),
+            bits.map(|b| b.to_string()).unwrap_or_default()
+        )
+        .unwrap();
+    }
+    writeln!(out, "</tbody>").unwrap();
+    writeln!(out, "</table>").unwrap();
+}
+
+fn render_charts(out: &mut String, charts: &[&Svg], dir: Option<&Path>) {
+    for chart in charts {
+        write!(out, "<div class=\"chart\">").unwrap();
+        match dir {
+            Some(dir) => {
+                let name = format!("{}.svg", chart.name);
+                let path = dir.join("charts").join(&name);
+                std::fs::write(&path, &chart.svg)
+                    .unwrap_or_else(|e| panic!("Could not write {}: {}", path.display(), e));
+                write!(
+                    out,
+                    "<img src=\"charts/{}\" alt=\"{}\">",
+                    escape(&name),
+                    escape(&chart.name)
+                )
+                .unwrap();
+            }
+            None => out.push_str(&chart.svg),
+        }
+        writeln!(out, "</div>").unwrap();
+    }
+}
+
+/// Writes `report.html` into `options.out_dir`, with its charts and raw data
+/// in `charts/` and `data/` beside it, or embedded when `single_file` is set.
+/// Charts are drawn as `plot` would, always as SVG.
+pub fn write(inputs: &Inputs, options: &PlotOptions, single_file: bool) {
+    let rows: Vec<Row> = (results::load_all(inputs).into_iter())
+        .filter(|r| options.selects(&r.job_name, &r.prover))
+        .collect();
+    let charts = charts::svg_all(&rows, &results::load_memory(inputs), options);
+
+    let out_dir = options.out_dir.as_path();
+    let dir = (!single_file).then_some(out_dir);
+    let subdirs: &[&str] = if single_file {
+        &[]
+    } else {
+        &["charts", "data"]
+    };
+    for path in
+        std::iter::once(out_dir.to_path_buf()).chain(subdirs.iter().map(|d| out_dir.join(d)))
+    {
+        std::fs::create_dir_all(&path).unwrap_or_else(|e| {
+            panic!(
+                "Could not create output directory {}: {}",
+                path.display(),
+                e
+            )
+        });
+    }
+
+    let jobs: BTreeSet<&str> = rows.iter().map(|r| r.job_name.as_str()).collect();
+    let mut out = String::new();
+    writeln!(out, "<!DOCTYPE html>").unwrap();
+    writeln!(out, "<html lang=\"en\">").unwrap();
+    writeln!(out, "<head>").unwrap();
+    writeln!(out, "<meta charset=\"utf-8\">").unwrap();
+    writeln!(out, "<title>Benchmark report</title>").unwrap();
+    writeln!(out, "<style>{}</style>", STYLE).unwrap();
+    writeln!(out, "</head>").unwrap();
+    writeln!(out, "<body>").unwrap();
+    writeln!(out, "<h1>Benchmark report</h1>").unwrap();
+
+    writeln!(out, "<p>Results from:</p>\n<ul>").unwrap();
+    for input in &inputs.inputs {
+        let label = match &input.machine {
+            Some(machine) => format!("{} ({})", input.path.display(), machine),
+            None => input.path.display().to_string(),
+        };
+        writeln!(out, "<li><code>{}</code></li>", escape(&label)).unwrap();
+    }
+    writeln!(out, "</ul>").unwrap();
+
+    writeln!(out, "<p>").unwrap();
+    writeln!(out, "<a href=\"#environment\">Environment</a>").unwrap();
+    for job in &jobs {
+        writeln!(out, "| <a href=\"#job-{0}\">{0}</a>", escape(job)).unwrap();
+    }
+    writeln!(out, "</p>").unwrap();
+
+    render_environment(&mut out, inputs);
+
+    for job in jobs {
+        let job_rows: Vec<&Row> = rows.iter().filter(|r| r.job_name == job).collect();
+        let job_charts: Vec<&Svg> = charts.iter().filter(|c| c.job == job).collect();
+        writeln!(out, "<h2 id=\"job-{0}\">{0}</h2>", escape(job)).unwrap();
+        render_raw_data(&mut out, job, &job_rows, dir);
+        render_summary(&mut out, &job_rows);
+        render_charts(&mut out, &job_charts, dir);
+    }
+
+    writeln!(out, "<script>{}</script>", SCRIPT).unwrap();
+    writeln!(out, "</body>").unwrap();
+    writeln!(out, "</html>").unwrap();
+
+    let path = out_dir.join("report.html");
+    std::fs::write(&path, out)
+        .unwrap_or_else(|e| panic!("Could not write {}: {}", path.display(), e));
+    println!("Wrote {}", path.display());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use std::fs;
+
+    // Two levels of one prover, as in files from before levels were part of
+    // the prover name
+    const RESULTS: &str = "\
+prover,job_name,job_size,proof_duration_millisec,verify_duration_millisec,proof_bytes,security_bits,machine
+miden,fib,10,1000,2,2048,96,e2-standard-4
+miden,fib,10,3000,4,2048,96,e2-standard-4
+miden,fib,10,5000,6,4096,128,e2-standard-4
+miden,sum,10,500,1,1024,96,e2-standard-4
+";
+
+    // Writes a report of `RESULTS` into `dir` with the plotter's own command
+    // line, and returns its HTML
+    fn report(dir: &Path, single_file: bool) -> String {
+        let results = dir.join("results.csv");
+        fs::write(&results, RESULTS).unwrap();
+        let out_dir = dir.join("report");
+        let mut args = vec![
+            String::from("plot"),
+            String::from("report"),
+            results.display().to_string(),
+            String::from("--allow-mixed-security"),
+            String::from("--metric"),
+            String::from("prove-time"),
+            String::from("--out-dir"),
+            out_dir.display().to_string(),
+        ];
+        if single_file {
+            args.push(String::from("--single-file"));
+        }
+        match crate::Cli::parse_from(args).command {
+            Some(crate::Command::Report {
+                inputs,
+                options,
+                single_file,
+            }) => write(&inputs, &options, single_file),
+            _ => unreachable!(),
+        }
+        fs::read_to_string(out_dir.join("report.html")).unwrap()
+    }
+
+    #[test]
+    fn summarizes_each_security_level() {
+        let dir = tempfile::tempdir().unwrap();
+        let html = report(dir.path(), false);
+
+        // Job size, runs, median prove time, verify time and proof size, and
+        // the level
+        for cells in [
+            "<td>10</td><td>2</td><td>2.00</td><td>3.00</td><td>2.0</td><td>96</td>",
+            "<td>10</td><td>1</td><td>5.00</td><td>6.00</td><td>4.0</td><td>128</td>",
+        ] {
+            assert!(html.contains(cells), "no {} in {}", cells, html);
+        }
+    }
+
+    #[test]
+    fn writes_data_and_charts_beside_the_report() {
+        let dir = tempfile::tempdir().unwrap();
+        let html = report(dir.path(), false);
+        let out_dir = dir.path().join("report");
+
+        assert!(html.contains("<h2 id=\"job-fib\">fib</h2>"));
+        assert!(html.contains("<h2 id=\"job-sum\">sum</h2>"));
+        assert!(html.contains("href=\"data/fib_0.csv\""));
+        let data = fs::read_to_string(out_dir.join("data/fib_0.csv")).unwrap();
+        assert_eq!(data.lines().count(), 4);
+        assert!(data.lines().skip(1).all(|l| l.starts_with("miden,fib,")));
+
+        assert!(html.contains("<img src=\"charts/proving_time_fib.svg\""));
+        assert!(out_dir.join("charts/proving_time_fib.svg").exists());
+        assert!(!html.contains("<svg"));
+    }
+
+    #[test]
+    fn single_file_embeds_data_and_charts() {
+        let dir = tempfile::tempdir().unwrap();
+        let html = report(dir.path(), true);
+        let out_dir = dir.path().join("report");
+
+        assert!(html.contains(
+            "download=\"fib_0.csv\" href=\"data:text/csv;charset=utf-8,prover,job_name,"
+        ));
+        assert!(html.contains("<svg"));
+        assert!(!html.contains("<img"));
+        assert!(!out_dir.join("data").exists());
+        assert!(!out_dir.join("charts").exists());
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// A results file, optionally labelled with the machine it was produced on:
+/// `[MACHINE=]FILE`. A `machine` column in the file takes precedence over
+/// the label.
+#[derive(Clone, Debug)]
+pub struct Input {
+    pub machine: Option<String>,
+    pub path: PathBuf,
+}
+
+impl FromStr for Input {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((machine, path)) if !machine.is_empty() && !path.is_empty() => Ok(Input {
+                machine: Some(String::from(machine)),
+                path: PathBuf::from(path),
+            }),
+            Some(_) => Err(format!("expected [MACHINE=]FILE, got {:?}", s)),
+            None => Ok(Input {
+                machine: None,
+                path: PathBuf::from(s),
+            }),
+        }
+    }
+}
+
+#[derive(clap::Args)]
+pub struct Inputs {
+    // CSV results files or logs of GCP batch instances, optionally labelled
+    // with the machine they ran on
+    #[arg(value_name = "[MACHINE=]FILE", required = true)]
+    pub inputs: Vec<Input>,
+
+    /// Compare results proved at different security levels
+    #[arg(long)]
+    pub allow_mixed_security: bool,
+}
+
+/// One measurement, normalized to the units used throughout the plotter.
+#[derive(Clone, Debug)]
+pub struct Row {
+    pub source: PathBuf,
+    pub machine: String,
+    pub prover: String,
+    pub job_name: String,
+    pub job_size: usize,
+    pub proof_secs: f32,
+    pub verify_millis: f32,
+    pub proof_bytes: f32,
+    /// Missing in files written before the level was recorded
+    pub security_bits: Option<u32>,
+    /// Missing where rayon chose, and in files written before it was recorded
+    pub threads: Option<usize>,
+}
+
+// Older result files record durations in microseconds, newer ones in
+// milliseconds. Returns the column index and the factor to milliseconds.
+fn duration_column(index: &HashMap<String, usize>, prefix: &str) -> (usize, f32) {
+    if let Some(c) = index.get(&format!("{}_millisec", prefix)) {
+        (*c, 1.0)
+    } else if let Some(c) = index.get(&format!("{}_microsec", prefix)) {
+        (*c, 1.0 / 1_000.0)
+    } else {
+        panic!("Missing column {}_millisec", prefix)
+    }
+}
+
+pub fn load_rows(input: &Input) -> Vec<Row> {
+    if input.path.extension().is_some_and(|e| e == "log") {
+        return crate::logs::load_rows(input);
+    }
+
+    let mut rdr = csv::Reader::from_path(&input.path)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", input.path.display(), e));
+
+    let index: HashMap<String, usize> = rdr
+        .headers()
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .map(|(c, hdr)| (String::from(hdr), c))
+        .collect();
+
+    let (proof_col, proof_factor) = duration_column(&index, "proof_duration");
+    let (verify_col, verify_factor) = duration_column(&index, "verify_duration");
+    let machine_col = index.get("machine").copied();
+    let security_col = index.get("security_bits").copied();
+    let threads_col = index.get("threads").copied();
+
+    let default_machine = input.machine.clone().unwrap_or_else(|| {
+        input
+            .path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+
+    rdr.records()
+        .filter_map(|row| row.ok())
+        .map(|row| {
+            let machine = machine_col
+                .map(|c| &row[c])
+                .filter(|m| !m.is_empty())
+                .map(String::from)
+                .unwrap_or_else(|| default_machine.clone());
+            let proof_millis = row[proof_col].parse::<f32>().unwrap() * proof_factor;
+            let verify_millis = row[verify_col].parse::<f32>().unwrap() * verify_factor;
+
+            Row {
+                source: input.path.clone(),
+                machine,
+                prover: String::from(&row[index["prover"]]),
+                job_name: String::from(&row[index["job_name"]]),
+                job_size: row[index["job_size"]].parse::<usize>().unwrap(),
+                // cast milliseconds to seconds for proof duration
+                proof_secs: proof_millis / 1_000.0,
+                verify_millis,
+                proof_bytes: row[index["proof_bytes"]].parse::<f32>().unwrap(),
+                security_bits: security_col.and_then(|c| row[c].parse::<u32>().ok()),
+                threads: threads_col.and_then(|c| row[c].parse::<usize>().ok()),
+            }
+        })
+        .collect()
+}
+
+/// Peak heap of one run of a job, the highest over its phases.
+#[derive(Clone, Debug)]
+pub struct MemoryRow {
+    pub prover: String,
+    pub job_name: String,
+    pub job_size: usize,
+    pub peak_bytes: u64,
+}
+
+// Files written before the configuration columns were added have none of
+// them, so their runs of a job cannot be told apart and share one peak.
+#[derive(serde::Deserialize)]
+struct AllocRow {
+    prover: String,
+    job_name: String,
+    job_size: usize,
+    #[serde(default)]
+    security_bits: Option<u32>,
+    #[serde(default)]
+    threads: Option<usize>,
+    #[serde(default)]
+    machine: Option<String>,
+    #[serde(default)]
+    repetition: u32,
+    peak_heap_bytes: u64,
+}
+
+// Prover, job, size, security level, threads, machine and repetition
+type RunKey = (
+    String,
+    String,
+    usize,
+    Option<u32>,
+    Option<usize>,
+    Option<String>,
+    u32,
+);
+
+/// Peak heap of every job, from the `allocations.csv` files that hosts built
+/// with `count-alloc` write next to their results. Inputs without one have no
+/// memory data.
+pub fn load_memory(inputs: &Inputs) -> Vec<MemoryRow> {
+    let paths: BTreeSet<PathBuf> = inputs
+        .inputs
+        .iter()
+        .map(|input| match input.path.parent() {
+            Some(dir) => dir.join("allocations.csv"),
+            None => PathBuf::from("allocations.csv"),
+        })
+        .filter(|path| path.exists())
+        .collect();
+
+    let mut peaks: BTreeMap<RunKey, u64> = BTreeMap::new();
+    for path in paths {
+        let mut rdr = csv::Reader::from_path(&path)
+            .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+        for row in rdr.deserialize::<AllocRow>() {
+            let row = row.unwrap_or_else(|e| panic!("Invalid row in {}: {}", path.display(), e));
+            let machine = row.machine.filter(|m| !m.is_empty());
+            let peak = peaks
+                .entry((
+                    row.prover,
+                    row.job_name,
+                    row.job_size,
+                    row.security_bits,
+                    row.threads,
+                    machine,
+                    row.repetition,
+                ))
+                .or_default();
+            *peak = (*peak).max(row.peak_heap_bytes);
+        }
+    }
+
+    peaks
+        .into_iter()
+        .map(|((prover, job_name, job_size, ..), peak_bytes)| MemoryRow {
+            prover,
+            job_name,
+            job_size,
+            peak_bytes,
+        })
+        .collect()
+}
+
+// Results proved at different security levels are not comparable, so they
+// are only mixed when explicitly allowed. Rows without a recorded level are
+// not checked.
+fn check_security(rows: &[Row], allow_mixed: bool) {
+    let mut levels: BTreeMap<u32, BTreeSet<&str>> = BTreeMap::new();
+    for row in rows {
+        if let Some(bits) = row.security_bits {
+            levels.entry(bits).or_default().insert(&row.prover);
+        }
+    }
+    if levels.len() <= 1 {
+        return;
+    }
+
+    let summary = levels
+        .iter()
+        .map(|(bits, provers)| {
+            let provers: Vec<&str> = provers.iter().copied().collect();
+            format!("{} bits ({})", bits, provers.join(", "))
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if allow_mixed {
+        eprintln!(
+            "Warning: comparing results at different security levels: {}",
+            summary
+        );
+    } else {
+        eprintln!(
+            "Results mix security levels: {}. Pass --allow-mixed-security to compare them anyway.",
+            summary
+        );
+        std::process::exit(1);
+    }
+}
+
+pub fn load_all(inputs: &Inputs) -> Vec<Row> {
+    let rows: Vec<Row> = inputs.inputs.iter().flat_map(load_rows).collect();
+    check_security(&rows, inputs.allow_mixed_security);
+    rows
+}
+
+/// Median of a non-empty set of samples.
+pub fn median(values: &mut [f32]) -> f32 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn inputs(dir: &std::path::Path) -> Inputs {
+        Inputs {
+            inputs: vec![Input {
+                machine: None,
+                path: dir.join("results.csv"),
+            }],
+            allow_mixed_security: false,
+        }
+    }
+
+    #[test]
+    fn memory_is_the_peak_of_each_run() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("allocations.csv"),
+            "prover,job_name,job_size,security_bits,threads,machine,repetition,phase,alloc_bytes,alloc_count,peak_heap_bytes\n\
+             miden,fib,10,96,1,,0,setup,0,0,100\n\
+             miden,fib,10,96,1,,0,prove,0,0,300\n\
+             miden,fib,10,96,1,,1,prove,0,0,200\n\
+             miden,fib,10,96,2,,0,prove,0,0,500\n\
+             miden,fib,10,128,1,,0,prove,0,0,700\n",
+        )
+        .unwrap();
+
+        let peaks: Vec<u64> = load_memory(&inputs(dir.path()))
+            .iter()
+            .map(|m| m.peak_bytes)
+            .collect();
+        assert_eq!(peaks, [300, 200, 500, 700]);
+    }
+
+    #[test]
+    fn memory_loads_files_without_run_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("allocations.csv"),
+            "prover,job_name,job_size,phase,alloc_bytes,alloc_count,peak_heap_bytes\n\
+             miden,fib,10,setup,0,0,100\n\
+             miden,fib,10,prove,0,0,300\n",
+        )
+        .unwrap();
+
+        let memory = load_memory(&inputs(dir.path()));
+        assert_eq!(memory.len(), 1);
+        assert_eq!(memory[0].peak_bytes, 300);
+    }
+}
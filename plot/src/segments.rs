@@ -0,0 +1,210 @@
+// Proof composition as recorded in the `proof_details` of the JSON output.
+// Only systems with continuations (RISC Zero) record segments.
+
+use crate::charts::{self, Draw, PlotOptions};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+struct JsonRow {
+    prover: String,
+    job_name: String,
+    job_size: usize,
+    proof_bytes: u32,
+    proof_details: Option<Details>,
+}
+
+#[derive(Deserialize)]
+struct Details {
+    variant: String,
+    // Missing when the prover did not report its session, as with Bonsai
+    segment_count: Option<usize>,
+    seal_bytes: usize,
+    #[serde(default)]
+    segments: Vec<Segment>,
+}
+
+#[derive(Deserialize)]
+struct Segment {
+    seal_bytes: usize,
+    hashfn: String,
+}
+
+struct Point {
+    job_size: usize,
+    segments: usize,
+    proof_kb: f32,
+}
+
+fn load(paths: &[PathBuf]) -> Vec<JsonRow> {
+    paths
+        .iter()
+        .flat_map(|path| {
+            let text = fs::read_to_string(path)
+                .unwrap_or_else(|err| panic!("Could not read {}: {}", path.display(), err));
+            text.lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(|l| {
+                    serde_json::from_str(l).unwrap_or_else(|err| {
+                        panic!("Invalid JSON line in {}: {}", path.display(), err)
+                    })
+                })
+                .collect::<Vec<JsonRow>>()
+        })
+        .collect()
+}
+
+fn print_row(row: &JsonRow, details: &Details) {
+    let hashfns: Vec<&str> =
+        details
+            .segments
+            .iter()
+            .map(|s| s.hashfn.as_str())
+            .fold(Vec::new(), |mut v, h| {
+                if !v.contains(&h) {
+                    v.push(h);
+                }
+                v
+            });
+    let largest = details.segments.iter().map(|s| s.seal_bytes).max();
+
+    println!(
+        "{:<20} {:<12} {:>8} {:<9} {:>8} {:>12.1} {:>12.1} {:>12} {}",
+        row.job_name,
+        row.prover,
+        row.job_size,
+        details.variant,
+        details
+            .segment_count
+            .map_or(String::from("-"), |n| n.to_string()),
+        details.seal_bytes as f32 / 1024.0,
+        row.proof_bytes as f32 / 1024.0,
+        largest.map_or(String::from("-"), |b| format!("{:.1}", b as f32 / 1024.0)),
+        hashfns.join(",")
+    );
+}
+
+// How the proof size of one job grows with its number of segments, a line
+// per prover with every point labelled with its job size
+struct SegmentsChart<'a> {
+    job: &'a str,
+    series: &'a BTreeMap<&'a str, Vec<Point>>,
+}
+
+impl Draw for SegmentsChart<'_> {
+    fn draw<DB: DrawingBackend>(&self, root_area: &DrawingArea<DB, Shift>, options: &PlotOptions) {
+        let series = self.series;
+        root_area.fill(&WHITE).unwrap();
+        let title = options.title("proof size by segments {job}", &[("job", self.job)]);
+        let root_area = root_area.titled(&title, ("sans-serif", 40)).unwrap();
+
+        let points = || series.values().flatten();
+        let xmax = points().map(|p| p.segments).max().unwrap_or(1) as f32;
+        let ymax = points().map(|p| p.proof_kb).fold(0.0, f32::max);
+
+        let mut cc = ChartBuilder::on(&root_area)
+            .margin(5)
+            .set_all_label_area_size(50)
+            .build_cartesian_2d(0.0..(xmax + 1.0), 0.0..(ymax * 1.2))
+            .unwrap();
+
+        cc.configure_mesh()
+            .disable_mesh()
+            .x_labels(xmax as usize + 2)
+            .x_desc("segments")
+            .y_desc("proof size (KB)")
+            .x_label_formatter(&|v| format!("{:.0}", v))
+            .y_label_formatter(&|v| format!("{:.0}", v))
+            .draw()
+            .unwrap();
+
+        for (index, (prover, points)) in series.iter().enumerate() {
+            let series = LineSeries::new(
+                points.iter().map(|p| (p.segments as f32, p.proof_kb)),
+                &Palette99::pick(index),
+            );
+            cc.draw_series(series)
+                .unwrap()
+                .label(*prover)
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 20, y)], Palette99::pick(index))
+                });
+
+            // Label each point with its job size
+            cc.draw_series(points.iter().map(|p| {
+                EmptyElement::at((p.segments as f32, p.proof_kb))
+                    + Circle::new((0, 0), 3, Palette99::pick(index).filled())
+                    + Text::new(
+                        format!("{}", p.job_size),
+                        (5, -15),
+                        ("sans-serif", 15).into_font(),
+                    )
+            }))
+            .unwrap();
+        }
+
+        cc.configure_series_labels()
+            .border_style(BLACK)
+            .draw()
+            .unwrap();
+
+        root_area.present().expect("Unable to write result to file");
+    }
+}
+
+/// Prints the receipt breakdown of every selected job and draws, per job,
+/// how the proof size grows with the number of segments into
+/// `segments_<job>` in `options.out_dir`.
+pub fn report(paths: &[PathBuf], options: &PlotOptions) {
+    let mut rows: Vec<JsonRow> = load(paths)
+        .into_iter()
+        .filter(|r| r.proof_details.is_some() && options.selects(&r.job_name, &r.prover))
+        .collect();
+    rows.sort_by(|a, b| {
+        (&a.job_name, &a.prover, a.job_size).cmp(&(&b.job_name, &b.prover, b.job_size))
+    });
+
+    if rows.is_empty() {
+        println!("No jobs with proof details found");
+        return;
+    }
+
+    println!(
+        "{:<20} {:<12} {:>8} {:<9} {:>8} {:>12} {:>12} {:>12} hashfn",
+        "job", "prover", "size", "variant", "segments", "seal KB", "proof KB", "largest KB"
+    );
+
+    let mut jobs: BTreeMap<&str, BTreeMap<&str, Vec<Point>>> = BTreeMap::new();
+    for row in &rows {
+        let details = row.proof_details.as_ref().unwrap();
+        print_row(row, details);
+        let Some(segments) = details.segment_count else {
+            continue;
+        };
+        jobs.entry(&row.job_name)
+            .or_default()
+            .entry(&row.prover)
+            .or_default()
+            .push(Point {
+                job_size: row.job_size,
+                segments,
+                proof_kb: row.proof_bytes as f32 / 1024.0,
+            });
+    }
+
+    charts::create_out_dir(options);
+    for (job, series) in &jobs {
+        let name = format!("segments_{}", job);
+        charts::render(&name, &SegmentsChart { job, series }, options);
+        println!(
+            "Wrote {}",
+            (options.out_dir)
+                .join(format!("{}.{}", name, options.format.extension()))
+                .display()
+        );
+    }
+}
@@ -0,0 +1,285 @@
+// Comparison tables for the README, rendered from result files.
+//
+// Each table has one column group per hash function (one column per job
+// size) and one row per system, grouped by machine. Combinations missing
+// from the data are left blank, and every table lists the files its numbers
+// came from so that the README stays traceable to committed data.
+
+use crate::results::{median, Row};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    Markdown,
+    Html,
+}
+
+#[derive(Clone, Copy)]
+enum Metric {
+    ProverTime,
+    VerifierTime,
+    ProofSize,
+}
+
+const METRICS: [Metric; 3] = [Metric::ProverTime, Metric::VerifierTime, Metric::ProofSize];
+
+impl Metric {
+    fn caption(&self) -> &'static str {
+        match self {
+            Self::ProverTime => "Prover time (sec)",
+            Self::VerifierTime => "Verifier time (ms)",
+            Self::ProofSize => "Proof size (KB)",
+        }
+    }
+
+    fn value(&self, row: &Row) -> f32 {
+        match self {
+            Self::ProverTime => row.proof_secs,
+            Self::VerifierTime => row.verify_millis,
+            Self::ProofSize => row.proof_bytes / 1024.0,
+        }
+    }
+
+    fn format(&self, v: f32) -> String {
+        match self {
+            Self::ProverTime | Self::VerifierTime => format!("{:.2}", v),
+            Self::ProofSize => format!("{:.1}", v),
+        }
+    }
+}
+
+/// Iterated hashing jobs and the hash function each one benchmarks.
+const HASH_JOBS: [(&str, &str); 3] = [
+    ("iter_sha2", "SHA256"),
+    ("iter_blake3", "BLAKE3"),
+    ("iter_rescue_prime", "RP64_256"),
+];
+
+pub fn system_name(prover: &str) -> &str {
+    match prover {
+        // Miden names levels above the default, as in `miden-128`
+        p if p == "miden" || p.starts_with("miden-") => "Miden VM",
+        "risczero" | "CpuSHA256" | "CpuPoseidon" | "Bonsai" => "RISC Zero",
+        other => other,
+    }
+}
+
+struct Layout<'a> {
+    // (hash function label, job name, job sizes)
+    columns: Vec<(&'static str, &'static str, Vec<usize>)>,
+    machines: Vec<&'a str>,
+    // (machine, prover) pairs in display order, with their row label
+    rows: Vec<(&'a str, &'a str, String)>,
+    // Data files with the machines whose results they hold
+    sources: BTreeMap<String, BTreeSet<&'a str>>,
+}
+
+fn layout(rows: &[Row]) -> Layout<'_> {
+    let rows: Vec<&Row> = rows
+        .iter()
+        .filter(|r| HASH_JOBS.iter().any(|(job, _)| *job == r.job_name))
+        .collect();
+
+    let columns = HASH_JOBS
+        .iter()
+        .map(|(job, hash)| {
+            let sizes: BTreeSet<usize> = rows
+                .iter()
+                .filter(|r| r.job_name == *job)
+                .map(|r| r.job_size)
+                .collect();
+            (*hash, *job, sizes.into_iter().collect::<Vec<_>>())
+        })
+        .filter(|(_, _, sizes)| !sizes.is_empty())
+        .collect();
+
+    let mut machines = Vec::new();
+    for r in &rows {
+        if !machines.contains(&r.machine.as_str()) {
+            machines.push(r.machine.as_str());
+        }
+    }
+
+    let mut provers_per_system: HashMap<&str, BTreeSet<&str>> = HashMap::new();
+    for r in &rows {
+        provers_per_system
+            .entry(system_name(&r.prover))
+            .or_default()
+            .insert(r.prover.as_str());
+    }
+
+    let mut table_rows = Vec::new();
+    for machine in &machines {
+        let mut provers: Vec<&str> = rows
+            .iter()
+            .filter(|r| r.machine == *machine)
+            .map(|r| r.prover.as_str())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        provers.sort_by_key(|p| (system_name(p), *p));
+
+        for prover in provers {
+            let system = system_name(prover);
+            let label = if provers_per_system[system].len() > 1 {
+                format!("{} ({})", system, prover)
+            } else {
+                String::from(system)
+            };
+            table_rows.push((*machine, prover, label));
+        }
+    }
+
+    let mut sources: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+    for r in &rows {
+        sources
+            .entry(r.source.display().to_string())
+            .or_default()
+            .insert(r.machine.as_str());
+    }
+
+    Layout {
+        columns,
+        machines,
+        rows: table_rows,
+        sources,
+    }
+}
+
+fn cell(
+    rows: &[Row],
+    metric: Metric,
+    machine: &str,
+    prover: &str,
+    job: &str,
+    size: usize,
+) -> String {
+    let mut values: Vec<f32> = rows
+        .iter()
+        .filter(|r| {
+            r.machine == machine && r.prover == prover && r.job_name == job && r.job_size == size
+        })
+        .map(|r| metric.value(r))
+        .collect();
+
+    if values.is_empty() {
+        String::from(" ")
+    } else {
+        metric.format(median(&mut values))
+    }
+}
+
+fn render_html(out: &mut String, rows: &[Row], layout: &Layout, metric: Metric) {
+    let width: usize = 2 + layout
+        .columns
+        .iter()
+        .map(|(_, _, s)| s.len())
+        .sum::<usize>();
+
+    writeln!(out, "<table>").unwrap();
+    writeln!(out, "    <thead>").unwrap();
+    writeln!(out, "        <tr>").unwrap();
+    writeln!(
+        out,
+        "            <th rowspan=2 colspan=2>{}</th>",
+        metric.caption()
+    )
+    .unwrap();
+    for (hash, _, sizes) in &layout.columns {
+        writeln!(out, "            <th colspan={}>{}</th>", sizes.len(), hash).unwrap();
+    }
+    writeln!(out, "        </tr>").unwrap();
+    writeln!(out, "        <tr>").unwrap();
+    for (_, _, sizes) in &layout.columns {
+        for size in sizes {
+            writeln!(out, "            <th>{}</th>", size).unwrap();
+        }
+    }
+    writeln!(out, "        </tr>").unwrap();
+    writeln!(out, "    </thead>").unwrap();
+    writeln!(out, "    <tbody>").unwrap();
+    for machine in &layout.machines {
+        writeln!(out, "        <tr>").unwrap();
+        writeln!(out, "            <td colspan={}>{}</td>", width, machine).unwrap();
+        writeln!(out, "        </tr>").unwrap();
+        for (_, prover, label) in layout.rows.iter().filter(|(m, _, _)| m == machine) {
+            writeln!(out, "        <tr>").unwrap();
+            writeln!(out, "            <td> </td>").unwrap();
+            writeln!(
+                out,
+                "            <td style=\"text-align:left\">{}</td>",
+                label
+            )
+            .unwrap();
+            for (_, job, sizes) in &layout.columns {
+                for size in sizes {
+                    let v = cell(rows, metric, machine, prover, job, *size);
+                    writeln!(out, "            <td>{}</td>", v).unwrap();
+                }
+            }
+            writeln!(out, "        </tr>").unwrap();
+        }
+    }
+    writeln!(out, "    </tbody>").unwrap();
+    writeln!(out, "</table>").unwrap();
+}
+
+fn render_markdown(out: &mut String, rows: &[Row], layout: &Layout, metric: Metric) {
+    write!(out, "| {} | System |", metric.caption()).unwrap();
+    for (hash, _, sizes) in &layout.columns {
+        for size in sizes {
+            write!(out, " {} {} |", hash, size).unwrap();
+        }
+    }
+    writeln!(out).unwrap();
+
+    write!(out, "| --- | --- |").unwrap();
+    for (_, _, sizes) in &layout.columns {
+        for _ in sizes {
+            write!(out, " ---: |").unwrap();
+        }
+    }
+    writeln!(out).unwrap();
+
+    for machine in &layout.machines {
+        let mut first = true;
+        for (_, prover, label) in layout.rows.iter().filter(|(m, _, _)| m == machine) {
+            let machine_cell = if first { machine } else { "" };
+            first = false;
+            write!(out, "| {} | {} |", machine_cell, label).unwrap();
+            for (_, job, sizes) in &layout.columns {
+                for size in sizes {
+                    write!(
+                        out,
+                        " {} |",
+                        cell(rows, metric, machine, prover, job, *size).trim()
+                    )
+                    .unwrap();
+                }
+            }
+            writeln!(out).unwrap();
+        }
+    }
+}
+
+/// Render the prover time, verifier time and proof size tables.
+pub fn render(rows: &[Row], format: Format) -> String {
+    let layout = layout(rows);
+    let mut out = String::new();
+
+    for metric in METRICS {
+        match format {
+            Format::Html => render_html(&mut out, rows, &layout, metric),
+            Format::Markdown => render_markdown(&mut out, rows, &layout, metric),
+        }
+        writeln!(out).unwrap();
+        for (source, machines) in &layout.sources {
+            let machines: Vec<&str> = machines.iter().copied().collect();
+            writeln!(out, "Data for {}: `{}`  ", machines.join(", "), source).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    out
+}
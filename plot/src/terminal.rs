@@ -0,0 +1,316 @@
+// Summary tables and line charts drawn as text, to check results on machines
+// without a display, e.g. over SSH right after a run.
+//
+// Both show the median of each job size, from the tables built by
+// `load_csv`. Job sizes are spaced evenly on the charts, as they usually grow
+// by orders of magnitude.
+
+use crate::Table;
+use std::collections::BTreeSet;
+
+#[derive(clap::Args)]
+pub struct ShowOptions {
+    /// Jobs to show; every job when omitted
+    #[arg(long = "job", value_name = "NAME")]
+    pub jobs: Vec<String>,
+
+    /// Width of the charts' plotting area, in characters
+    #[arg(long, default_value_t = 60)]
+    pub width: usize,
+
+    /// Height of the charts' plotting area, in lines
+    #[arg(long, default_value_t = 12)]
+    pub height: usize,
+
+    /// Draw with ASCII characters only, for terminals without Unicode
+    #[arg(long)]
+    pub ascii: bool,
+
+    /// Print the tables only
+    #[arg(long)]
+    pub no_charts: bool,
+}
+
+struct Glyphs {
+    markers: &'static [char],
+    line: char,
+    axis: char,
+    tick: char,
+    corner: char,
+    rule: char,
+}
+
+const UNICODE: Glyphs = Glyphs {
+    markers: &['●', '■', '▲', '◆', '★', '✚', '○', '□'],
+    line: '·',
+    axis: '│',
+    tick: '┤',
+    corner: '└',
+    rule: '─',
+};
+
+const ASCII: Glyphs = Glyphs {
+    markers: &['*', '#', 'o', '+', 'x', '@', '%', '&'],
+    line: '.',
+    axis: '|',
+    tick: '+',
+    corner: '+',
+    rule: '-',
+};
+
+// Median of the samples of every job size. `points` is sorted by job size.
+fn medians(points: &[(usize, f32)]) -> Vec<(usize, f32)> {
+    points
+        .chunk_by(|a, b| a.0 == b.0)
+        .map(|samples| {
+            let mut values: Vec<f32> = samples.iter().map(|(_, v)| *v).collect();
+            values.sort_by(|a, b| a.total_cmp(b));
+            let n = values.len();
+            (samples[0].0, (values[(n - 1) / 2] + values[n / 2]) / 2.0)
+        })
+        .collect()
+}
+
+// Four significant digits, without an exponent
+fn number(v: f32) -> String {
+    let digits = if v == 0.0 {
+        0
+    } else {
+        (3 - v.abs().log10().floor() as i32).clamp(0, 3) as usize
+    };
+    format!("{:.*}", digits, v)
+}
+
+/// One prover's medians in a job's table and chart.
+struct Line<'a> {
+    prover: &'a str,
+    medians: Vec<(usize, f32)>,
+}
+
+fn render_table(out: &mut String, lines: &[Line], sizes: &[usize]) {
+    let header: Vec<String> = sizes.iter().map(|s| s.to_string()).collect();
+    let cells: Vec<Vec<String>> = lines
+        .iter()
+        .map(|line| {
+            (sizes.iter())
+                .map(|size| match line.medians.iter().find(|(s, _)| s == size) {
+                    Some((_, v)) => number(*v),
+                    None => String::from("-"),
+                })
+                .collect()
+        })
+        .collect();
+
+    let first = (lines.iter().map(|l| l.prover.len()))
+        .chain(std::iter::once("prover".len()))
+        .max()
+        .unwrap();
+    let widths: Vec<usize> = (0..sizes.len())
+        .map(|i| {
+            (cells.iter().map(|row| row[i].chars().count()))
+                .chain(std::iter::once(header[i].len()))
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    out.push_str(&format!("{:<first$}", "prover"));
+    for (h, w) in header.iter().zip(&widths) {
+        out.push_str(&format!("  {:>w$}", h));
+    }
+    out.push('\n');
+    for (line, row) in lines.iter().zip(&cells) {
+        out.push_str(&format!("{:<first$}", line.prover));
+        for (cell, w) in row.iter().zip(&widths) {
+            out.push_str(&format!("  {:>w$}", cell));
+        }
+        out.push('\n');
+    }
+}
+
+fn render_chart(out: &mut String, lines: &[Line], sizes: &[usize], options: &ShowOptions) {
+    let glyphs = if options.ascii { &ASCII } else { &UNICODE };
+    let (width, height) = (options.width.max(2), options.height.max(2));
+    let max = (lines.iter().flat_map(|l| l.medians.iter().map(|(_, v)| *v))).fold(0.0, f32::max);
+    let max = if max > 0.0 { max } else { 1.0 };
+
+    let column = |size: &usize| match sizes.len() {
+        1 => width / 2,
+        n => sizes.binary_search(size).unwrap() * (width - 1) / (n - 1),
+    };
+    let row = |v: f32| height - 1 - (v / max * (height - 1) as f32).round() as usize;
+
+    let mut grid = vec![vec![' '; width]; height];
+    // Lines first, so that every marker stays visible where they cross
+    for line in lines {
+        for pair in line.medians.windows(2) {
+            let ((c0, v0), (c1, v1)) = (
+                (column(&pair[0].0), pair[0].1),
+                (column(&pair[1].0), pair[1].1),
+            );
+            let between = (c0 + 1..c1).map(|c| {
+                let v = v0 + (v1 - v0) * (c - c0) as f32 / (c1 - c0) as f32;
+                (row(v), c)
+            });
+            for (r, c) in between {
+                let cell = &mut grid[r][c];
+                if *cell == ' ' {
+                    *cell = glyphs.line;
+                }
+            }
+        }
+    }
+    for (index, line) in lines.iter().enumerate() {
+        let marker = glyphs.markers[index % glyphs.markers.len()];
+        for (size, v) in &line.medians {
+            grid[row(*v)][column(size)] = marker;
+        }
+    }
+
+    // The y axis is labelled at its top, middle and bottom
+    let labels = [
+        (0, number(max)),
+        (
+            height / 2,
+            number(max * (height - 1 - height / 2) as f32 / (height - 1) as f32),
+        ),
+        (height - 1, number(0.0)),
+    ];
+    let margin = labels.iter().map(|(_, l)| l.len()).max().unwrap();
+    for (r, cells) in grid.iter().enumerate() {
+        let cells: String = cells.iter().collect();
+        match labels.iter().find(|(at, _)| *at == r) {
+            Some((_, label)) => out.push_str(&format!("{:>margin$} {}", label, glyphs.tick)),
+            None => out.push_str(&format!("{:>margin$} {}", "", glyphs.axis)),
+        }
+        out.push_str(cells.trim_end());
+        out.push('\n');
+    }
+    out.push_str(&format!("{:>margin$} {}", "", glyphs.corner));
+    out.push_str(&glyphs.rule.to_string().repeat(width));
+    out.push('\n');
+
+    // Job sizes under their columns, leaving out any that would overlap
+    let mut axis = vec![' '; width + 1];
+    let mut free = 0;
+    for size in sizes {
+        let label: Vec<char> = size.to_string().chars().collect();
+        let start = ((column(size) + 1).saturating_sub(label.len() / 2))
+            .min(axis.len().saturating_sub(label.len()));
+        if start < free || start + label.len() > axis.len() {
+            continue;
+        }
+        axis[start..start + label.len()].copy_from_slice(&label);
+        free = start + label.len() + 1;
+    }
+    let axis: String = axis.into_iter().collect();
+    out.push_str(&format!(
+        "{:>margin$} {}  (job size)\n",
+        "",
+        axis.trim_end()
+    ));
+
+    let legend: Vec<String> = (lines.iter().enumerate())
+        .map(|(index, line)| {
+            let marker = glyphs.markers[index % glyphs.markers.len()];
+            format!("{} {}", marker, line.prover)
+        })
+        .collect();
+    out.push_str(&format!("{:>margin$} {}\n", "", legend.join("  ")));
+}
+
+/// Renders a table of medians, and unless disabled a chart of them, for
+/// every metric and job.
+pub fn render(
+    proof_durations: &Table,
+    verify_durations: &Table,
+    proof_sizes: &Table,
+    options: &ShowOptions,
+) -> String {
+    let all_data = [
+        (proof_durations, "proving_time", "s"),
+        (verify_durations, "verifying_time", "ms"),
+        (proof_sizes, "proof_size", "bytes"),
+    ];
+
+    let mut out = String::new();
+    for (table, name, unit) in all_data {
+        let jobs: BTreeSet<&String> = (table.keys())
+            .map(|(job, _)| job)
+            .filter(|job| options.jobs.is_empty() || options.jobs.contains(job))
+            .collect();
+
+        for job in jobs {
+            let lines: Vec<Line> = (table.iter())
+                .filter(|((j, _), _)| j == job)
+                .map(|((_, prover), points)| Line {
+                    prover,
+                    medians: medians(points),
+                })
+                .collect();
+            let sizes: Vec<usize> = (lines.iter())
+                .flat_map(|l| l.medians.iter().map(|(s, _)| *s))
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+
+            out.push_str(&format!("{} {} ({})\n\n", name, job, unit));
+            render_table(&mut out, &lines, &sizes);
+            if !options.no_charts {
+                out.push('\n');
+                render_chart(&mut out, &lines, &sizes, options);
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn renders_tables_and_charts() {
+        let mut proof_durations = Table::new();
+        proof_durations.insert(
+            (String::from("fib"), String::from("fast")),
+            vec![(10, 1.0), (10, 3.0), (100, 4.0), (1000, 8.0)],
+        );
+        proof_durations.insert(
+            (String::from("fib"), String::from("slow")),
+            vec![(10, 2.0), (100, 8.0)],
+        );
+        let empty = BTreeMap::new();
+        let options = ShowOptions {
+            jobs: Vec::new(),
+            width: 11,
+            height: 5,
+            ascii: true,
+            no_charts: false,
+        };
+
+        // Medians of the samples of each size; 1000 is left off the axis,
+        // where it would overlap 100
+        let expected = "\
+proving_time fib (s)
+
+prover     10    100   1000
+fast    2.000  4.000  8.000
+slow    2.000  8.000      -
+
+8.000 +     #   .*
+      |   ..  ..
+4.000 + ....*.
+      |#..
+    0 +
+      +-----------
+      10   100  (job size)
+      * fast  # slow
+
+";
+        let out = render(&proof_durations, &empty, &empty, &options);
+        assert_eq!(out, expected);
+    }
+}
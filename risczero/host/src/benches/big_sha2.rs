@@ -1,9 +1,12 @@
-use crate::helpers::inner_receipt_size_bytes;
+use crate::helpers::{
+    execute, inner_receipt_bytes, inner_receipt_size_bytes, prove, receipt_details, Proof,
+};
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use risc0_zkvm::serde::{from_slice, to_vec};
 use risc0_zkvm::sha::{Digest, DIGEST_WORDS};
-use risc0_zkvm::{ExecutorEnv, Receipt};
+use risc0_zkvm::ExecutorEnv;
 use rustbench::Benchmark;
+use tracing::info_span;
 
 type GuestInput = Vec<u8>;
 
@@ -13,11 +16,13 @@ pub struct Job<'a> {
     pub prover: crate::provers::Name,
 }
 
-pub fn new_jobs() -> Vec<<Job<'static> as Benchmark>::Spec> {
+pub const DEFAULT_SIZES: &[u32] = &[1024, 2048, 4096, 8192];
+
+pub fn new_jobs(sizes: &[u32]) -> Vec<<Job<'static> as Benchmark>::Spec> {
     let mut rand = StdRng::seed_from_u64(1337);
     let mut jobs = Vec::new();
-    for job_size in [1024, 2048, 4096, 8192] {
-        let mut spec = vec![0; job_size];
+    for &job_size in sizes {
+        let mut spec = vec![0; job_size as usize];
         for i in 0..spec.len() {
             spec[i] = rand.next_u32() as u8;
         }
@@ -33,8 +38,9 @@ impl Benchmark for Job<'_> {
     const NAME: &'static str = "big_sha2";
     type Spec = GuestInput;
     type ComputeOut = Digest;
-    type ProofType = Receipt;
+    type ProofType = Proof;
     type Prover = crate::provers::Name;
+    type Artifact = crate::helpers::Artifact;
 
     fn prover_name(&self) -> String {
         self.prover.to_string()
@@ -45,18 +51,40 @@ impl Benchmark for Job<'_> {
     }
 
     fn output_size_bytes(_output: &Self::ComputeOut, proof: &Self::ProofType) -> u32 {
-        proof.journal.len() as u32
+        proof.receipt.journal.len() as u32
     }
 
     fn proof_size_bytes(proof: &Self::ProofType) -> u32 {
-        inner_receipt_size_bytes(&proof.inner)
+        inner_receipt_size_bytes(&proof.receipt.inner)
+    }
+
+    fn public_output(_output: &Self::ComputeOut, proof: &Self::ProofType) -> Vec<u8> {
+        proof.receipt.journal.clone()
+    }
+
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8> {
+        inner_receipt_bytes(&proof.receipt.inner)
+    }
+
+    fn security_bits(_proof: &Self::ProofType) -> u32 {
+        crate::provers::SECURITY_BITS
+    }
+
+    fn prover_security_bits(_prover: &Self::Prover) -> Option<u32> {
+        Some(crate::provers::SECURITY_BITS)
+    }
+
+    fn proof_details(proof: &Self::ProofType) -> Option<serde_json::Value> {
+        receipt_details(proof)
     }
 
     fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self {
-        let env = ExecutorEnv::builder()
-            .add_input(&to_vec(&spec).unwrap())
-            .build()
-            .unwrap();
+        let env = info_span!("executor_env").in_scope(|| {
+            ExecutorEnv::builder()
+                .add_input(&to_vec(&spec).unwrap())
+                .build()
+                .unwrap()
+        });
 
         Job {
             spec: spec.clone(),
@@ -70,18 +98,26 @@ impl Benchmark for Job<'_> {
     }
 
     fn guest_compute(&mut self) -> (Self::ComputeOut, Self::ProofType) {
-        let prover = self.prover.get_prover();
-        let Receipt { inner, journal } = prover.prove_elf(self.env.clone(), METHOD_ELF).unwrap();
+        let proof = prove(&self.prover, self.env.clone(), METHOD_ELF);
 
-        let guest_output: Digest = from_slice::<Vec<u8>, _>(&journal)
+        let guest_output: Digest = from_slice::<Vec<u8>, _>(&proof.receipt.journal)
             .unwrap()
             .try_into()
             .unwrap();
-        (guest_output, Receipt { inner, journal })
+        (guest_output, proof)
+    }
+
+    fn guest_execute(&mut self) -> Self::ComputeOut {
+        let journal = execute(self.env.clone(), METHOD_ELF);
+
+        from_slice::<Vec<u8>, _>(&journal)
+            .unwrap()
+            .try_into()
+            .unwrap()
     }
 
     fn verify_proof(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> bool {
-        let result = proof.verify(METHOD_ID);
+        let result = proof.receipt.verify(METHOD_ID);
 
         match result {
             Ok(_) => true,
@@ -91,4 +127,12 @@ impl Benchmark for Job<'_> {
             }
         }
     }
+
+    fn artifact(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact {
+        crate::helpers::Artifact::new(METHOD_ID, &proof.receipt)
+    }
+
+    fn verify_artifact(artifact: &Self::Artifact) -> bool {
+        artifact.verify()
+    }
 }
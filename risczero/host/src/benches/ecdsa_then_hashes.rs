@@ -1,4 +1,6 @@
-use crate::helpers::inner_receipt_size_bytes;
+use crate::helpers::{
+    execute, inner_receipt_bytes, inner_receipt_size_bytes, prove, receipt_details, Proof,
+};
 use k256::{
     ecdsa::{signature::Signer, Signature, SigningKey},
     EncodedPoint,
@@ -6,8 +8,9 @@ use k256::{
 use rand_core::OsRng;
 use risc0_zkvm::serde::to_vec;
 use risc0_zkvm::sha::DIGEST_WORDS;
-use risc0_zkvm::{ExecutorEnv, Receipt};
+use risc0_zkvm::ExecutorEnv;
 use rustbench::Benchmark;
+use tracing::info_span;
 
 #[derive(Clone)]
 pub struct Spec {
@@ -37,10 +40,10 @@ fn gen_spec(nhashes: u32) -> Spec {
     }
 }
 
-pub fn new_jobs() -> Vec<<Job<'static> as Benchmark>::Spec> {
-    [1, 100, 200, 300, 400, 500, 600, 700, 800, 900, 1000]
-        .map(gen_spec)
-        .to_vec()
+pub const DEFAULT_SIZES: &[u32] = &[1, 100, 200, 300, 400, 500, 600, 700, 800, 900, 1000];
+
+pub fn new_jobs(sizes: &[u32]) -> Vec<<Job<'static> as Benchmark>::Spec> {
+    sizes.iter().copied().map(gen_spec).collect()
 }
 
 const METHOD_ID: [u32; DIGEST_WORDS] = risczero_benchmark_methods::ECDSA_THEN_HASHES_ID;
@@ -50,8 +53,9 @@ impl Benchmark for Job<'_> {
     const NAME: &'static str = "ecdsa_then_hashes";
     type Spec = Spec;
     type ComputeOut = ();
-    type ProofType = Receipt;
+    type ProofType = Proof;
     type Prover = crate::provers::Name;
+    type Artifact = crate::helpers::Artifact;
 
     fn prover_name(&self) -> String {
         self.prover.to_string()
@@ -62,26 +66,48 @@ impl Benchmark for Job<'_> {
     }
 
     fn output_size_bytes(_output: &Self::ComputeOut, proof: &Self::ProofType) -> u32 {
-        proof.journal.len() as u32
+        proof.receipt.journal.len() as u32
     }
 
     fn proof_size_bytes(proof: &Self::ProofType) -> u32 {
-        inner_receipt_size_bytes(&proof.inner)
+        inner_receipt_size_bytes(&proof.receipt.inner)
+    }
+
+    fn public_output(_output: &Self::ComputeOut, proof: &Self::ProofType) -> Vec<u8> {
+        proof.receipt.journal.clone()
+    }
+
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8> {
+        inner_receipt_bytes(&proof.receipt.inner)
+    }
+
+    fn security_bits(_proof: &Self::ProofType) -> u32 {
+        crate::provers::SECURITY_BITS
+    }
+
+    fn prover_security_bits(_prover: &Self::Prover) -> Option<u32> {
+        Some(crate::provers::SECURITY_BITS)
+    }
+
+    fn proof_details(proof: &Self::ProofType) -> Option<serde_json::Value> {
+        receipt_details(proof)
     }
 
     fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self {
-        let env = ExecutorEnv::builder()
-            .add_input(
-                &to_vec(&(
-                    spec.encoded_verifying_key,
-                    &spec.message,
-                    spec.signature,
-                    spec.nhashes,
-                ))
-                .unwrap(),
-            )
-            .build()
-            .unwrap();
+        let env = info_span!("executor_env").in_scope(|| {
+            ExecutorEnv::builder()
+                .add_input(
+                    &to_vec(&(
+                        spec.encoded_verifying_key,
+                        &spec.message,
+                        spec.signature,
+                        spec.nhashes,
+                    ))
+                    .unwrap(),
+                )
+                .build()
+                .unwrap()
+        });
 
         Job {
             spec: spec.clone(),
@@ -99,14 +125,17 @@ impl Benchmark for Job<'_> {
     }
 
     fn guest_compute(&mut self) -> (Self::ComputeOut, Self::ProofType) {
-        let prover = self.prover.get_prover();
-        let Receipt { inner, journal } = prover.prove_elf(self.env.clone(), METHOD_ELF).unwrap();
+        let proof = prove(&self.prover, self.env.clone(), METHOD_ELF);
+
+        ((), proof)
+    }
 
-        ((), Receipt { inner, journal })
+    fn guest_execute(&mut self) -> Self::ComputeOut {
+        execute(self.env.clone(), METHOD_ELF);
     }
 
     fn verify_proof(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> bool {
-        let result = proof.verify(METHOD_ID);
+        let result = proof.receipt.verify(METHOD_ID);
 
         match result {
             Ok(_) => true,
@@ -116,4 +145,12 @@ impl Benchmark for Job<'_> {
             }
         }
     }
+
+    fn artifact(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact {
+        crate::helpers::Artifact::new(METHOD_ID, &proof.receipt)
+    }
+
+    fn verify_artifact(artifact: &Self::Artifact) -> bool {
+        artifact.verify()
+    }
 }
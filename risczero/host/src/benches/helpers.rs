@@ -1,35 +1,169 @@
-// TODO: is there a cleaner way to compute the proof size? Something directly
-// exposed in the risc0 API?
-// pub fn inner_receipt_size_bytes(proof: &risc0_zkvm::receipt::InnerReceipt) -> u32 {
-//     let bytes: Vec<u8> = bincode::serialize(proof).unwrap();
-//     bytes.len() as u32
-// }
-
+use crate::provers::Name;
 use risc0_zkvm::receipt::InnerReceipt::*;
 use risc0_zkvm::receipt::SegmentReceipt;
 use risc0_zkvm::receipt::SegmentReceipts;
+use risc0_zkvm::sha::DIGEST_WORDS;
+use risc0_zkvm::{
+    Executor, ExecutorEnv, MemoryImage, Program, Receipt, VerifierContext, MEM_SIZE, PAGE_SIZE,
+};
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+
+pub fn inner_receipt_bytes(proof: &risc0_zkvm::receipt::InnerReceipt) -> Vec<u8> {
+    bincode::serialize(proof).unwrap()
+}
 
 pub fn inner_receipt_size_bytes(proof: &risc0_zkvm::receipt::InnerReceipt) -> u32 {
-    match proof {
+    inner_receipt_bytes(proof).len() as u32
+}
+
+/// A receipt with the number of segments of the session it proves, which a
+/// succinct receipt no longer shows.
+pub struct Proof {
+    pub receipt: Receipt,
+    /// Unknown when Bonsai proved the session remotely
+    pub segment_count: Option<usize>,
+}
+
+/// A receipt with the image it was proved for, as saved with `--save-proofs`.
+#[derive(Serialize, Deserialize)]
+pub struct Artifact {
+    pub image_id: [u32; DIGEST_WORDS],
+    pub receipt: Receipt,
+}
+
+impl Artifact {
+    pub fn new(image_id: [u32; DIGEST_WORDS], proof: &Receipt) -> Self {
+        // Receipts are not Clone, so copy the inner receipt through bincode
+        let inner = bincode::deserialize(&inner_receipt_bytes(&proof.inner)).unwrap();
+        Artifact {
+            image_id,
+            receipt: Receipt {
+                inner,
+                journal: proof.journal.clone(),
+            },
+        }
+    }
+
+    pub fn verify(&self) -> bool {
+        match self.receipt.verify(self.image_id) {
+            Ok(_) => true,
+            Err(err) => {
+                println!("{}", err);
+                false
+            }
+        }
+    }
+}
+
+/// How a receipt is made up, stored as the proof details of every job.
+#[derive(Serialize)]
+pub struct ReceiptBreakdown {
+    /// Flat, Succinct or Fake
+    pub variant: &'static str,
+    /// Segments of the proved session, whatever the receipt keeps of them
+    pub segment_count: Option<usize>,
+    pub seal_bytes: usize,
+    pub segments: Vec<SegmentSeal>,
+}
+
+#[derive(Serialize)]
+pub struct SegmentSeal {
+    pub index: u32,
+    pub seal_bytes: usize,
+    pub hashfn: String,
+}
+
+fn seal_bytes(seal: &[u32]) -> usize {
+    std::mem::size_of_val(seal)
+}
+
+pub fn receipt_breakdown(proof: &Proof) -> ReceiptBreakdown {
+    match &proof.receipt.inner {
         Flat(SegmentReceipts(vec)) => {
-            let segments = vec.len();
-            println!("Number of segments = {}", segments);
-            for seg in vec {
-                let SegmentReceipt {
-                    seal,
-                    index,
-                    hashfn: _,
-                } = seg;
-                let size = seal.len();
-                println!("    Seal {} length: u32 elements = {}", index, size)
+            let segments: Vec<SegmentSeal> = vec
+                .iter()
+                .map(
+                    |SegmentReceipt {
+                         seal,
+                         index,
+                         hashfn,
+                     }| SegmentSeal {
+                        index: *index,
+                        seal_bytes: seal_bytes(seal),
+                        hashfn: hashfn.clone(),
+                    },
+                )
+                .collect();
+            ReceiptBreakdown {
+                variant: "Flat",
+                segment_count: Some(segments.len()),
+                seal_bytes: segments.iter().map(|s| s.seal_bytes).sum(),
+                segments,
             }
         }
-        Succinct(succinct) => {
-            let size = succinct.seal.len();
-            println!("Seal length: u32 elements = {}", size)
+        // Succinct receipts compress all segments into a single seal
+        Succinct(succinct) => ReceiptBreakdown {
+            variant: "Succinct",
+            segment_count: proof.segment_count,
+            seal_bytes: seal_bytes(&succinct.seal),
+            segments: Vec::new(),
+        },
+        Fake => ReceiptBreakdown {
+            variant: "Fake",
+            segment_count: proof.segment_count,
+            seal_bytes: 0,
+            segments: Vec::new(),
+        },
+    }
+}
+
+pub fn receipt_details(proof: &Proof) -> Option<serde_json::Value> {
+    Some(serde_json::to_value(receipt_breakdown(proof)).unwrap())
+}
+
+// Proving times have always included loading the ELF and building its memory
+// image, as `prove_elf` does, so both stay inside the timed region rather than
+// in setup.
+fn load_image(elf: &[u8]) -> MemoryImage {
+    let _span = info_span!("load_elf").entered();
+    let program = Program::load_elf(elf, MEM_SIZE as u32).unwrap();
+    MemoryImage::new(&program, PAGE_SIZE as u32).unwrap()
+}
+
+/// Runs the guest without proving, returning its journal.
+pub fn execute(env: ExecutorEnv<'_>, elf: &[u8]) -> Vec<u8> {
+    let image = load_image(elf);
+    let session = info_span!("execute")
+        .in_scope(|| Executor::new(env, image).run())
+        .unwrap();
+    session.journal
+}
+
+// Executes the guest and proves the resulting session as two separate steps,
+// so that they show up as distinct spans in traces. Bonsai only proves whole
+// images remotely.
+pub fn prove(prover: &Name, env: ExecutorEnv<'_>, elf: &[u8]) -> Proof {
+    let image = load_image(elf);
+    let ctx = VerifierContext::default();
+    let prover_impl = prover.get_prover();
+
+    match prover {
+        Name::Bonsai => Proof {
+            receipt: prover_impl.prove(env, &ctx, image).unwrap(),
+            segment_count: None,
+        },
+        Name::CpuSHA256 | Name::CpuPoseidon => {
+            let session = info_span!("execute")
+                .in_scope(|| Executor::new(env, image).run())
+                .unwrap();
+            let receipt = info_span!("prove_session")
+                .in_scope(|| prover_impl.prove_session(&ctx, &session))
+                .unwrap();
+            Proof {
+                receipt,
+                segment_count: Some(session.segments.len()),
+            }
         }
-        Fake => {}
     }
-    let bytes: Vec<u8> = bincode::serialize(proof).unwrap();
-    bytes.len() as u32
 }
@@ -1,9 +1,12 @@
-use crate::helpers::inner_receipt_size_bytes;
+use crate::helpers::{
+    execute, inner_receipt_bytes, inner_receipt_size_bytes, prove, receipt_details, Proof,
+};
 use risc0_zkvm::serde::{from_slice, to_vec};
 use risc0_zkvm::sha::Digest;
 use risc0_zkvm::sha::DIGEST_WORDS;
-use risc0_zkvm::{ExecutorEnv, Receipt};
+use risc0_zkvm::ExecutorEnv;
 use rustbench::Benchmark;
+use tracing::info_span;
 
 pub struct Job<'a> {
     pub spec: u32,
@@ -11,9 +14,11 @@ pub struct Job<'a> {
     pub prover: crate::provers::Name,
 }
 
-pub fn new_jobs() -> Vec<<Job<'static> as Benchmark>::Spec> {
-    //    vec![1, 100, 200, 300, 400, 500, 600, 700, 800, 900, 1000]
-    vec![0]
+//    pub const DEFAULT_SIZES: &[u32] = &[1, 100, 200, 300, 400, 500, 600, 700, 800, 900, 1000];
+pub const DEFAULT_SIZES: &[u32] = &[0];
+
+pub fn new_jobs(sizes: &[u32]) -> Vec<<Job<'static> as Benchmark>::Spec> {
+    sizes.to_vec()
 }
 
 const METHOD_ID: [u32; DIGEST_WORDS] = risczero_benchmark_methods::ITER_SHA2_ID;
@@ -23,8 +28,9 @@ impl Benchmark for Job<'_> {
     const NAME: &'static str = "iter_sha2";
     type Spec = u32;
     type ComputeOut = Digest;
-    type ProofType = Receipt;
+    type ProofType = Proof;
     type Prover = crate::provers::Name;
+    type Artifact = crate::helpers::Artifact;
 
     fn prover_name(&self) -> String {
         self.prover.to_string()
@@ -35,21 +41,43 @@ impl Benchmark for Job<'_> {
     }
 
     fn output_size_bytes(_output: &Self::ComputeOut, proof: &Self::ProofType) -> u32 {
-        proof.journal.len() as u32
+        proof.receipt.journal.len() as u32
     }
 
     fn proof_size_bytes(proof: &Self::ProofType) -> u32 {
-        inner_receipt_size_bytes(&proof.inner)
+        inner_receipt_size_bytes(&proof.receipt.inner)
+    }
+
+    fn public_output(_output: &Self::ComputeOut, proof: &Self::ProofType) -> Vec<u8> {
+        proof.receipt.journal.clone()
+    }
+
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8> {
+        inner_receipt_bytes(&proof.receipt.inner)
+    }
+
+    fn security_bits(_proof: &Self::ProofType) -> u32 {
+        crate::provers::SECURITY_BITS
+    }
+
+    fn prover_security_bits(_prover: &Self::Prover) -> Option<u32> {
+        Some(crate::provers::SECURITY_BITS)
+    }
+
+    fn proof_details(proof: &Self::ProofType) -> Option<serde_json::Value> {
+        receipt_details(proof)
     }
 
     fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self {
         let spec_slice: [u32; 1] = [*spec];
         let initial_bytes: [u8; 32] = [0u8; 32];
-        let env = ExecutorEnv::builder()
-            .add_input(&to_vec(&spec_slice).unwrap())
-            .add_input(&to_vec(&initial_bytes.to_vec()).unwrap())
-            .build()
-            .unwrap();
+        let env = info_span!("executor_env").in_scope(|| {
+            ExecutorEnv::builder()
+                .add_input(&to_vec(&spec_slice).unwrap())
+                .add_input(&to_vec(&initial_bytes.to_vec()).unwrap())
+                .build()
+                .unwrap()
+        });
 
         Job {
             spec: *spec,
@@ -76,17 +104,22 @@ impl Benchmark for Job<'_> {
     }
 
     fn guest_compute(&mut self) -> (Self::ComputeOut, Self::ProofType) {
-        let prover = self.prover.get_prover();
-        let Receipt { inner, journal } = prover.prove_elf(self.env.clone(), METHOD_ELF).unwrap();
+        let proof = prove(&self.prover, self.env.clone(), METHOD_ELF);
+
+        let guest_output: Digest = from_slice(&proof.receipt.journal)
+            .expect("Journal output should output to data committed by guest");
+
+        (guest_output, proof)
+    }
 
-        let guest_output: Digest =
-            from_slice(&journal).expect("Journal output should output to data committed by guest");
+    fn guest_execute(&mut self) -> Self::ComputeOut {
+        let journal = execute(self.env.clone(), METHOD_ELF);
 
-        (guest_output, Receipt { inner, journal })
+        from_slice(&journal).expect("Journal output should output to data committed by guest")
     }
 
     fn verify_proof(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> bool {
-        let result = proof.verify(METHOD_ID);
+        let result = proof.receipt.verify(METHOD_ID);
 
         match result {
             Ok(_) => true,
@@ -96,4 +129,12 @@ impl Benchmark for Job<'_> {
             }
         }
     }
+
+    fn artifact(&self, _output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact {
+        crate::helpers::Artifact::new(METHOD_ID, &proof.receipt)
+    }
+
+    fn verify_artifact(artifact: &Self::Artifact) -> bool {
+        artifact.verify()
+    }
 }
@@ -1,4 +1,5 @@
 use risc0_zkvm::prove::Prover;
+use std::fmt;
 use std::rc::Rc;
 
 #[derive(Clone, PartialEq, Copy)]
@@ -8,15 +9,17 @@ pub enum Name {
     Bonsai,
 }
 
-impl Name {
-    pub fn to_string(&self) -> String {
-        match self {
-            Self::CpuSHA256 => String::from("CpuSHA256"),
-            Self::CpuPoseidon => String::from("CpuPoseidon"),
-            Self::Bonsai => String::from("Bonsai"),
-        }
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::CpuSHA256 => "CpuSHA256",
+            Self::CpuPoseidon => "CpuPoseidon",
+            Self::Bonsai => "Bonsai",
+        })
     }
+}
 
+impl Name {
     pub fn get_prover(&self) -> Rc<dyn Prover> {
         match self {
             Self::CpuSHA256 => risc0_zkvm::prove::get_prover("cpu"),
@@ -28,4 +31,8 @@ impl Name {
 
 // pub const DEFAULT: Name = Name::CpuSHA256;
 
+// Every RISC Zero prover targets the same conjectured security level,
+// whichever hash function it uses; see https://github.com/risc0/risc0/#security
+pub const SECURITY_BITS: u32 = 100;
+
 pub const PROVERS: [Name; 3] = [Name::Bonsai, Name::CpuSHA256, Name::CpuPoseidon];
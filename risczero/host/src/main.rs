@@ -1,17 +1,26 @@
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::path::{Path, PathBuf};
 
 mod benches;
 
 use benches::*;
-use rustbench::{init_logging, run_jobs};
+use rustbench::{init_logging, run_jobs, verify_jobs, Benchmark, RunOptions, Suite};
+
+// Counts heap allocations so that every job reports per-phase heap usage
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOC: rustbench::CountingAlloc = rustbench::CountingAlloc;
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
 struct Cli {
-    // CSV output file
+    // CSV output file; needed by every command but `list`
     #[arg(long, value_name = "FILE")]
-    out: PathBuf,
+    out: Option<PathBuf>,
+
+    #[command(flatten)]
+    run: RunOptions,
 
     #[command(subcommand)]
     command: Command,
@@ -29,6 +38,34 @@ enum Command {
     BubbleSort,
     Xp,
     Xp2,
+    /// Verify the proofs saved with --save-proofs in DIR, without proving
+    Verify {
+        dir: PathBuf,
+    },
+    /// Print the name of every bench, one per line
+    List,
+}
+
+impl Cli {
+    fn out(&self) -> &Path {
+        self.out.as_deref().unwrap_or_else(|| {
+            Cli::command()
+                .error(
+                    ErrorKind::MissingRequiredArgument,
+                    "--out <FILE> is required",
+                )
+                .exit()
+        })
+    }
+}
+
+// Every subcommand but these runs a bench of the same name
+fn bench_names() -> Vec<String> {
+    Cli::command()
+        .get_subcommands()
+        .map(|c| c.get_name().replace('-', "_"))
+        .filter(|name| !["all", "verify", "list"].contains(&name.as_str()))
+        .collect()
 }
 
 use crate::Command::*;
@@ -45,52 +82,155 @@ const ALL: [Command; 9] = [
     Xp2,
 ];
 
-fn run_command(cli: &Cli, command: &Command, provers: &Vec<provers::Name>) -> () {
+const SYSTEM: &str = "risczero";
+
+// Runs one bench with the sizes and options given on the command line or in
+// the suite; benches left out of the suite are skipped.
+fn run_bench<B: Benchmark<Prover = provers::Name>>(
+    cli: &Cli,
+    suite: Option<&Suite>,
+    bench: &str,
+    default_sizes: &[u32],
+    new_jobs: fn(&[u32]) -> Vec<B::Spec>,
+    provers: &Vec<provers::Name>,
+) where
+    B::Spec: Sync,
+{
+    match cli.run.for_bench(suite, SYSTEM, bench) {
+        Some(options) => {
+            let specs = new_jobs(&options.sizes_or(default_sizes));
+            let _ = run_jobs::<B>(cli.out(), &specs, provers, &options);
+        }
+        None => println!("Skipping {}: not in the suite", bench),
+    }
+}
+
+fn run_command(
+    cli: &Cli,
+    suite: Option<&Suite>,
+    command: &Command,
+    provers: &Vec<provers::Name>,
+) -> () {
     match command {
         All => {
             for c in ALL {
-                run_command(cli, &c, provers)
+                run_command(cli, suite, &c, provers)
             }
         }
-        EcdsaThenHashes => {
-            let _ = run_jobs::<ecdsa_then_hashes::Job>(
-                &cli.out,
-                &ecdsa_then_hashes::new_jobs(),
-                provers,
-            );
-        }
-        IterEcdsa => {
-            let _ = run_jobs::<iter_ecdsa::Job>(&cli.out, &iter_ecdsa::new_jobs(), provers);
-        }
-        IterSha2 => {
-            let _ = run_jobs::<iter_sha2::Job>(&cli.out, &iter_sha2::new_jobs(), provers);
-        }
-        IterSha2Pure => {
-            let _ = run_jobs::<iter_sha2_pure::Job>(&cli.out, &iter_sha2_pure::new_jobs(), provers);
-        }
-        BigSha2 => {
-            let _ = run_jobs::<big_sha2::Job>(&cli.out, &big_sha2::new_jobs(), provers);
-        }
-        Fact => {
-            let _ = run_jobs::<fact::Job>(&cli.out, &fact::new_jobs(), provers);
-        }
-        BubbleSort => {
-            let _ = run_jobs::<bubble_sort::Job>(&cli.out, &bubble_sort::new_jobs(), provers);
-        }
-        Xp => {
-            let _ = run_jobs::<xp::Job>(&cli.out, &xp::new_jobs(), provers);
-        }
-        Xp2 => {
-            let _ = run_jobs::<xp2::Job>(&cli.out, &xp2::new_jobs(), provers);
-        }
+        EcdsaThenHashes => run_bench::<ecdsa_then_hashes::Job>(
+            cli,
+            suite,
+            "ecdsa_then_hashes",
+            ecdsa_then_hashes::DEFAULT_SIZES,
+            ecdsa_then_hashes::new_jobs,
+            provers,
+        ),
+        IterEcdsa => run_bench::<iter_ecdsa::Job>(
+            cli,
+            suite,
+            "iter_ecdsa",
+            iter_ecdsa::DEFAULT_SIZES,
+            iter_ecdsa::new_jobs,
+            provers,
+        ),
+        IterSha2 => run_bench::<iter_sha2::Job>(
+            cli,
+            suite,
+            "iter_sha2",
+            iter_sha2::DEFAULT_SIZES,
+            iter_sha2::new_jobs,
+            provers,
+        ),
+        IterSha2Pure => run_bench::<iter_sha2_pure::Job>(
+            cli,
+            suite,
+            "iter_sha2_pure",
+            iter_sha2_pure::DEFAULT_SIZES,
+            iter_sha2_pure::new_jobs,
+            provers,
+        ),
+        BigSha2 => run_bench::<big_sha2::Job>(
+            cli,
+            suite,
+            "big_sha2",
+            big_sha2::DEFAULT_SIZES,
+            big_sha2::new_jobs,
+            provers,
+        ),
+        Fact => run_bench::<fact::Job>(
+            cli,
+            suite,
+            "fact",
+            fact::DEFAULT_SIZES,
+            fact::new_jobs,
+            provers,
+        ),
+        BubbleSort => run_bench::<bubble_sort::Job>(
+            cli,
+            suite,
+            "bubble_sort",
+            bubble_sort::DEFAULT_SIZES,
+            bubble_sort::new_jobs,
+            provers,
+        ),
+        Xp => run_bench::<xp::Job>(cli, suite, "xp", xp::DEFAULT_SIZES, xp::new_jobs, provers),
+        Verify { dir } => verify_all(cli, dir),
+        List => {}
+        Xp2 => run_bench::<xp2::Job>(
+            cli,
+            suite,
+            "xp2",
+            xp2::DEFAULT_SIZES,
+            xp2::new_jobs,
+            provers,
+        ),
     }
 }
 
+fn verify_all(cli: &Cli, dir: &Path) {
+    verify_jobs::<ecdsa_then_hashes::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<iter_ecdsa::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<iter_sha2::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<iter_sha2_pure::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<big_sha2::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<fact::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<bubble_sort::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<xp::Job>(cli.out(), dir, &cli.run);
+    verify_jobs::<xp2::Job>(cli.out(), dir, &cli.run);
+}
+
 fn main() {
     init_logging();
     let cli = Cli::parse();
 
-    let provers =
+    if cli.command == List {
+        for bench in bench_names() {
+            println!("{}", bench);
+        }
+        return;
+    }
+    // Every other command writes to --out, so check for it before any work
+    cli.out();
+
+    if let Verify { dir } = &cli.command {
+        verify_all(&cli, dir);
+        return;
+    }
+
+    let suite = cli.run.load_suite();
+    let suite = suite.as_ref();
+
+    for bits in cli.run.security_levels(suite, SYSTEM).into_iter().flatten() {
+        if bits != provers::SECURITY_BITS {
+            panic!(
+                "RISC Zero only proves at {} bits of security, not {}",
+                provers::SECURITY_BITS,
+                bits
+            );
+        }
+    }
+
+    let mut provers =
         if std::env::var("BONSAI_API_URL").is_ok() && std::env::var("BONSAI_API_KEY").is_ok() {
             provers::PROVERS.to_vec()
         } else {
@@ -100,5 +240,12 @@ fn main() {
             provers
         };
 
-    run_command(&cli, &cli.command, &provers);
+    if let Some(names) = suite
+        .and_then(|s| s.system(SYSTEM))
+        .and_then(|s| s.provers.as_ref())
+    {
+        provers.retain(|prover| names.contains(&prover.to_string()));
+    }
+
+    run_command(&cli, suite, &cli.command, &provers);
 }
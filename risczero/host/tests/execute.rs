@@ -0,0 +1,15 @@
+use std::process::Command;
+
+// Executes every bench at every default size without proving, and fails
+// unless each output matches the host's. Catches broken guests in seconds
+// rather than the minutes a proof takes.
+#[test]
+fn every_bench_matches_host() {
+    let status = Command::new(env!("CARGO_BIN_EXE_host"))
+        .arg("--out")
+        .arg(std::env::temp_dir().join("risczero-execute-check.csv"))
+        .args(["--check", "execute", "all"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,105 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicU64, Ordering::Relaxed};
+
+static ALLOCATED: AtomicU64 = AtomicU64::new(0);
+static COUNT: AtomicU64 = AtomicU64::new(0);
+static LIVE: AtomicU64 = AtomicU64::new(0);
+static PEAK: AtomicU64 = AtomicU64::new(0);
+
+/// Global allocator that counts heap allocations on top of the system
+/// allocator. Host binaries opt in with
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: rustbench::CountingAlloc = rustbench::CountingAlloc;
+/// ```
+///
+/// after which every job records [`AllocStats`] for each of its phases.
+pub struct CountingAlloc;
+
+fn record_alloc(size: usize) {
+    let size = size as u64;
+    ALLOCATED.fetch_add(size, Relaxed);
+    COUNT.fetch_add(1, Relaxed);
+    let live = LIVE.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(live, Relaxed);
+}
+
+fn record_dealloc(size: usize) {
+    LIVE.fetch_sub(size as u64, Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_dealloc(layout.size());
+    }
+
+    // Counted as a fresh allocation of the new size, since growing a buffer
+    // usually means copying it.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Whether the host binary installed [`CountingAlloc`].
+pub fn installed() -> bool {
+    COUNT.load(Relaxed) > 0
+}
+
+/// Heap usage of one phase of a job.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AllocStats {
+    /// Total bytes allocated during the phase, whether freed or not.
+    pub bytes: u64,
+    pub count: u64,
+    /// Highest live heap during the phase, including whatever was already
+    /// live when it started.
+    pub peak_bytes: u64,
+}
+
+/// Measures the allocations between `start` and `finish`. Phases must not
+/// overlap, since the peak is tracked globally.
+pub struct Phase {
+    bytes: u64,
+    count: u64,
+}
+
+impl Phase {
+    pub fn start() -> Self {
+        PEAK.store(LIVE.load(Relaxed), Relaxed);
+        Phase {
+            bytes: ALLOCATED.load(Relaxed),
+            count: COUNT.load(Relaxed),
+        }
+    }
+
+    pub fn finish(self) -> AllocStats {
+        AllocStats {
+            bytes: ALLOCATED.load(Relaxed) - self.bytes,
+            count: COUNT.load(Relaxed) - self.count,
+            peak_bytes: PEAK.load(Relaxed),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+// Proofs saved to disk with `--save-proofs`, so that they can be verified by
+// another process, possibly on a weaker machine than the one that proved them.
+
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{job_file_name, Metrics};
+
+/// A proof as saved to disk, with enough about its job to report on it.
+#[derive(Serialize, Deserialize)]
+pub struct SavedProof<A> {
+    pub prover: String,
+    pub job_name: String,
+    pub job_size: u32,
+    pub security_bits: u32,
+    pub proof_bytes: u32,
+    pub proof_digest: String,
+    /// See [`crate::Benchmark::Artifact`]
+    pub artifact: A,
+}
+
+// Every bench gets a directory of its own, so that loading never has to guess
+// which artifact type a file holds.
+fn bench_dir(dir: &Path, job_name: &str) -> PathBuf {
+    dir.join(job_name)
+}
+
+pub fn save<A: Serialize>(dir: &Path, metrics: &Metrics, artifact: A) {
+    let dir = bench_dir(dir, &metrics.job_name);
+    fs::create_dir_all(&dir).expect("Could not create proofs directory");
+    let path = dir.join(job_file_name(metrics, "bin"));
+
+    let saved = SavedProof {
+        prover: metrics.prover.clone(),
+        job_name: metrics.job_name.clone(),
+        job_size: metrics.job_size,
+        security_bits: metrics.security_bits,
+        proof_bytes: metrics.proof_bytes,
+        proof_digest: metrics.proof_digest.clone(),
+        artifact,
+    };
+    let file = File::create(&path)
+        .unwrap_or_else(|e| panic!("Could not create {}: {}", path.display(), e));
+    bincode::serialize_into(BufWriter::new(file), &saved)
+        .unwrap_or_else(|e| panic!("Could not write {}: {}", path.display(), e));
+}
+
+/// Every proof of `job_name` saved under `dir`, in file name order.
+pub fn load<A: DeserializeOwned>(dir: &Path, job_name: &str) -> Vec<SavedProof<A>> {
+    let dir = bench_dir(dir, job_name);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension() == Some(OsStr::new("bin")))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let file = File::open(path)
+                .unwrap_or_else(|e| panic!("Could not open {}: {}", path.display(), e));
+            bincode::deserialize_from(BufReader::new(file))
+                .unwrap_or_else(|e| panic!("Invalid proof {}: {}", path.display(), e))
+        })
+        .collect()
+}
@@ -1,9 +1,28 @@
-use std::fs::OpenOptions;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use log::info;
-use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::info_span;
+
+pub use alloc::{AllocStats, CountingAlloc};
+pub use machine::Machine;
+pub use suite::{BenchSuite, Suite, SystemSuite};
+
+mod alloc;
+mod artifact;
+mod machine;
+mod outliers;
+#[cfg(feature = "profile")]
+mod profile;
+mod suite;
+#[cfg(feature = "chrome-trace")]
+mod trace;
 
 pub struct Metrics {
     pub prover: String,
@@ -13,6 +32,23 @@ pub struct Metrics {
     pub verify_duration: Duration,
     pub output_bytes: u32,
     pub proof_bytes: u32,
+    pub security_bits: u32,
+    /// SHA-256 of the public output, see [`Benchmark::public_output`]
+    pub output_digest: String,
+    /// SHA-256 of the serialized proof, see [`Benchmark::proof_to_bytes`]
+    pub proof_digest: String,
+    /// Size of the rayon pool the job ran on; rayon's default when `None`
+    pub threads: Option<usize>,
+    /// Name of the machine profile the job was confined to, if any
+    pub machine: Option<String>,
+    pub repetition: u32,
+    /// Samples of the same job discarded as outliers and re-run
+    pub discarded: u32,
+    /// Heap usage per phase, only recorded when [`CountingAlloc`] is installed
+    pub allocations: Vec<(&'static str, AllocStats)>,
+    /// Benchmark-specific description of the proof, see
+    /// [`Benchmark::proof_details`]
+    pub proof_details: Option<serde_json::Value>,
 }
 
 impl Metrics {
@@ -25,17 +61,41 @@ impl Metrics {
             verify_duration: Duration::default(),
             output_bytes: 0,
             proof_bytes: 0,
+            security_bits: 0,
+            output_digest: String::new(),
+            proof_digest: String::new(),
+            threads: None,
+            machine: None,
+            repetition: 0,
+            discarded: 0,
+            allocations: Vec::new(),
+            proof_details: None,
+        }
+    }
+
+    fn record_phase(&mut self, name: &'static str, phase: alloc::Phase) {
+        if alloc::installed() {
+            self.allocations.push((name, phase.finish()));
         }
     }
 
     pub fn println(&self, prefix: &str) {
-        info!("{}prover:             {:?}", prefix, &self.prover);
-        info!("{}job_name:           {:?}", prefix, &self.job_name);
-        info!("{}job_size:           {:?}", prefix, &self.job_size);
-        info!("{}proof_duration:     {:?}", prefix, &self.proof_duration);
-        info!("{}verify_duration:    {:?}", prefix, &self.verify_duration);
-        info!("{}output_bytes:       {:?}", prefix, &self.output_bytes);
-        info!("{}proof_bytes:        {:?}", prefix, &self.proof_bytes);
+        info!("{}prover:             {:?}", prefix, self.prover);
+        info!("{}job_name:           {:?}", prefix, self.job_name);
+        info!("{}job_size:           {:?}", prefix, self.job_size);
+        info!("{}proof_duration:     {:?}", prefix, self.proof_duration);
+        info!("{}verify_duration:    {:?}", prefix, self.verify_duration);
+        info!("{}output_bytes:       {:?}", prefix, self.output_bytes);
+        info!("{}proof_bytes:        {:?}", prefix, self.proof_bytes);
+        info!("{}security_bits:      {:?}", prefix, self.security_bits);
+        info!("{}output_digest:      {}", prefix, self.output_digest);
+        info!("{}proof_digest:       {}", prefix, self.proof_digest);
+        for (phase, stats) in &self.allocations {
+            info!(
+                "{}alloc {:<13} {} bytes in {} allocations, peak {} bytes",
+                prefix, phase, stats.bytes, stats.count, stats.peak_bytes
+            );
+        }
     }
 }
 
@@ -44,7 +104,11 @@ pub trait Benchmark {
     type Spec;
     type ComputeOut: Eq + core::fmt::Debug;
     type ProofType;
-    type Prover;
+    /// Prints as the name its jobs report with `prover_name`
+    type Prover: core::fmt::Display;
+    /// Everything a standalone verifier needs: the proof, its public inputs
+    /// and outputs, and the program it claims to be for
+    type Artifact: Serialize + DeserializeOwned;
 
     fn prover_name(&self) -> String;
 
@@ -54,6 +118,28 @@ pub trait Benchmark {
 
     fn proof_size_bytes(proof: &Self::ProofType) -> u32;
 
+    /// Public output as committed to by the proof (e.g. the journal)
+    fn public_output(output: &Self::ComputeOut, proof: &Self::ProofType) -> Vec<u8>;
+
+    /// The proof as it would be sent to a verifier
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8>;
+
+    /// Conjectured security level achieved by a proof, in bits
+    fn security_bits(proof: &Self::ProofType) -> u32;
+
+    /// Security level the proofs of `prover` achieve, when it is known
+    /// before proving. Resuming counts only earlier results at this level;
+    /// otherwise the prover name has to tell levels apart
+    fn prover_security_bits(_prover: &Self::Prover) -> Option<u32> {
+        None
+    }
+
+    /// Structured details about a proof (e.g. how it splits into segments),
+    /// stored with the job in the JSON output
+    fn proof_details(_proof: &Self::ProofType) -> Option<serde_json::Value> {
+        None
+    }
+
     fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self;
 
     fn spec(&self) -> &Self::Spec;
@@ -63,37 +149,77 @@ pub trait Benchmark {
     }
 
     fn guest_compute(&mut self) -> (Self::ComputeOut, Self::ProofType);
+
+    /// Runs the guest program without proving, for `--check execute`
+    fn guest_execute(&mut self) -> Self::ComputeOut;
     fn verify_proof(&self, output: &Self::ComputeOut, proof: &Self::ProofType) -> bool;
 
-    fn run(&mut self) -> Metrics {
+    fn artifact(&self, output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact;
+
+    /// Checks a saved proof without the job that made it
+    fn verify_artifact(artifact: &Self::Artifact) -> bool;
+
+    fn run(&mut self, options: &RunOptions, context: &JobContext) -> Metrics {
         let mut metrics = Metrics::new(
             String::from(Self::NAME),
             Self::job_size(self.spec()),
             Self::prover_name(self),
         );
+        metrics.threads = context.threads;
+        metrics.machine = context.machine.clone();
+        metrics.repetition = context.repetition;
 
-        let (g_output, proof) = {
+        let phase = alloc::Phase::start();
+        let (g_output, proof) = info_span!("prove").in_scope(|| {
+            #[cfg(feature = "profile")]
+            let profile = options.profile.then(profile::JobProfile::start);
             let start = Instant::now();
             let result = self.guest_compute();
             metrics.proof_duration = start.elapsed();
+            #[cfg(feature = "profile")]
+            if let Some(profile) = profile {
+                profile.finish(&context.run_dir.join("profiles"), &job_file_stem(&metrics));
+            }
             result
-        };
+        });
+        metrics.record_phase("prove", phase);
 
-        if let Some(h_output) = self.host_compute() {
-            assert_eq!(g_output, h_output);
+        let phase = alloc::Phase::start();
+        let h_output = info_span!("host_compute").in_scope(|| self.host_compute());
+        metrics.record_phase("host_compute", phase);
+        if let Some(h_output) = h_output {
+            assert_eq!(g_output, h_output, "Guest and host outputs differ");
         }
 
-        metrics.output_bytes = Self::output_size_bytes(&g_output, &proof);
-        metrics.proof_bytes = Self::proof_size_bytes(&proof);
+        let phase = alloc::Phase::start();
+        info_span!("serialize").in_scope(|| {
+            metrics.output_bytes = Self::output_size_bytes(&g_output, &proof);
+            metrics.proof_bytes = Self::proof_size_bytes(&proof);
+            metrics.security_bits = Self::security_bits(&proof);
+            metrics.output_digest = sha256_hex(&Self::public_output(&g_output, &proof));
+            metrics.proof_digest = sha256_hex(&Self::proof_to_bytes(&proof));
+            metrics.proof_details = Self::proof_details(&proof);
+        });
+        metrics.record_phase("serialize", phase);
 
-        let verify_proof = {
+        let phase = alloc::Phase::start();
+        let verify_proof = info_span!("verify").in_scope(|| {
             let start = Instant::now();
             let result = self.verify_proof(&g_output, &proof);
             metrics.verify_duration = start.elapsed();
             result
-        };
+        });
+        metrics.record_phase("verify", phase);
+
+        assert!(verify_proof, "Proof failed verification");
 
-        assert_eq!(verify_proof, true);
+        if options.save_proofs {
+            artifact::save(
+                &context.run_dir.join("proofs"),
+                &metrics,
+                self.artifact(&g_output, &proof),
+            );
+        }
 
         metrics
     }
@@ -103,7 +229,201 @@ pub fn init_logging() {
     env_logger::init();
 }
 
-#[derive(Serialize)]
+/// Options shared by every host binary, flattened into their command line.
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct RunOptions {
+    /// TOML suite selecting benches, sizes, provers, repetitions, thread
+    /// counts and security levels; command line options take precedence
+    #[arg(long, value_name = "FILE")]
+    pub suite: Option<PathBuf>,
+
+    /// Job sizes, instead of each bench's defaults
+    #[arg(long, value_name = "SIZE", value_delimiter = ',')]
+    pub sizes: Vec<u32>,
+
+    /// Number of times to run every job [default: 1]
+    #[arg(long, value_name = "N")]
+    pub repetitions: Option<u32>,
+
+    /// Number of extra runs allowed per job to replace repetitions whose
+    /// proof time is an outlier [default: the number of repetitions]
+    #[arg(long, value_name = "N")]
+    pub max_reruns: Option<u32>,
+
+    /// Thread counts to run every job with; rayon's default when omitted
+    #[arg(long, value_name = "N", value_delimiter = ',')]
+    pub threads: Vec<usize>,
+
+    /// Emulate a smaller machine: run on the profile's number of cores and
+    /// cap the heap at its memory. Either a built-in profile (e2-highmem-2,
+    /// e2-standard-4, e2-highmem-4, e2-standard-8) or one defined in the suite
+    #[arg(long, value_name = "NAME")]
+    pub machine: Option<String>,
+
+    /// Instead of benchmarking, check a property of every job
+    #[arg(long, value_enum, value_name = "MODE")]
+    pub check: Option<Check>,
+
+    /// Security level to prove at, in bits; each system rejects levels it
+    /// cannot provide. Defaults to each system's own default
+    #[arg(long, value_name = "BITS")]
+    pub security: Option<u32>,
+
+    /// Save every proof with what is needed to verify it to `proofs/` next to
+    /// the output file, for the `verify` command
+    #[arg(long)]
+    pub save_proofs: bool,
+
+    /// Skip jobs that already have every repetition in the output file, to
+    /// pick up an interrupted run
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Write a Chrome/Perfetto trace of every job to `traces/` next to the
+    /// output file
+    #[cfg(feature = "chrome-trace")]
+    #[arg(long)]
+    pub trace: bool,
+
+    /// Sample the prover while it proves and write a flamegraph and a pprof
+    /// profile of every job to `profiles/` next to the output file
+    #[cfg(feature = "profile")]
+    #[arg(long)]
+    pub profile: bool,
+}
+
+impl RunOptions {
+    pub fn load_suite(&self) -> Option<Suite> {
+        self.suite.as_deref().map(Suite::load)
+    }
+
+    /// Security levels to prove at: the one given on the command line, else
+    /// those listed in the suite, else the system's default (`None`).
+    pub fn security_levels(&self, suite: Option<&Suite>, system: &str) -> Vec<Option<u32>> {
+        match (self.security, suite.and_then(|s| s.system(system))) {
+            (Some(bits), _) => vec![Some(bits)],
+            (
+                None,
+                Some(SystemSuite {
+                    security: Some(levels),
+                    ..
+                }),
+            ) => levels.iter().copied().map(Some).collect(),
+            _ => vec![None],
+        }
+    }
+
+    /// The machine profile to emulate, given on the command line or in the
+    /// suite. Panics when no such profile exists.
+    pub fn machine_profile(&self) -> Option<Machine> {
+        let suite = self.load_suite();
+        let name = self
+            .machine
+            .clone()
+            .or_else(|| suite.as_ref()?.machine.clone())?;
+        let machine = match &suite {
+            Some(suite) => suite.machine(&name),
+            None => Machine::builtin(&name),
+        };
+        Some(machine.unwrap_or_else(|| {
+            panic!(
+                "Unknown machine {:?}; built-in machines are {}",
+                name,
+                Machine::builtin_names().join(", ")
+            )
+        }))
+    }
+
+    /// Options for one bench of `system` with the suite's settings filled
+    /// in, or `None` when a suite is given and does not list the bench.
+    pub fn for_bench(&self, suite: Option<&Suite>, system: &str, bench: &str) -> Option<Self> {
+        let mut options = self.clone();
+        if let Some(suite) = suite {
+            let config = suite.system(system)?.benches.get(bench)?;
+            if options.sizes.is_empty() {
+                options.sizes = config.sizes.clone().unwrap_or_default();
+            }
+            if options.repetitions.is_none() {
+                options.repetitions = config.repetitions.or(suite.repetitions);
+            }
+            if options.max_reruns.is_none() {
+                options.max_reruns = config.max_reruns.or(suite.max_reruns);
+            }
+            if options.threads.is_empty() {
+                options.threads = (config.threads.as_ref())
+                    .or(suite.threads.as_ref())
+                    .cloned()
+                    .unwrap_or_default();
+            }
+        }
+        Some(options)
+    }
+
+    /// The job sizes to run, falling back to the bench's defaults.
+    pub fn sizes_or(&self, defaults: &[u32]) -> Vec<u32> {
+        if self.sizes.is_empty() {
+            defaults.to_vec()
+        } else {
+            self.sizes.clone()
+        }
+    }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Check {
+    /// Prove every job at least twice and fail unless the public outputs
+    /// match; also reports whether the proofs are byte-identical
+    Determinism,
+    /// Only execute every job, without proving, and fail unless its output
+    /// matches the host's; writes no results
+    Execute,
+}
+
+/// Identifies one run of a job within a sweep.
+pub struct JobContext {
+    /// Where per-job artifacts are written
+    pub run_dir: PathBuf,
+    pub job_number: usize,
+    pub threads: Option<usize>,
+    pub machine: Option<String>,
+    pub repetition: u32,
+}
+
+/// Directory holding the output file, where per-job artifacts are written.
+pub fn run_dir(out_path: &Path) -> PathBuf {
+    match out_path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// File name, without extension, identifying one job in the run directory.
+pub fn job_file_stem(metrics: &Metrics) -> String {
+    let mut stem = format!(
+        "{}_{}_{}",
+        metrics.job_name, metrics.job_size, metrics.prover
+    );
+    if let Some(machine) = &metrics.machine {
+        stem.push_str(&format!("_{}", machine));
+    }
+    if let Some(threads) = metrics.threads {
+        stem.push_str(&format!("_t{}", threads));
+    }
+    if metrics.repetition > 0 {
+        stem.push_str(&format!("_r{}", metrics.repetition));
+    }
+    stem
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+pub fn job_file_name(metrics: &Metrics, extension: &str) -> String {
+    format!("{}.{}", job_file_stem(metrics), extension)
+}
+
+#[derive(Default, Serialize)]
 struct CsvRow<'a> {
     prover: &'a str,
     job_name: &'a str,
@@ -111,65 +431,657 @@ struct CsvRow<'a> {
     proof_duration_millisec: u128,
     verify_duration_millisec: u128,
     proof_bytes: u32,
+    security_bits: u32,
+    output_digest: &'a str,
+    proof_digest: &'a str,
+    threads: Option<usize>,
+    machine: Option<&'a str>,
+    repetition: u32,
+    discarded: u32,
+}
+
+// Written by the `verify` command, which does not prove.
+#[derive(Default, Serialize)]
+struct VerifyRow<'a> {
+    prover: &'a str,
+    job_name: &'a str,
+    job_size: u32,
+    verify_duration_millisec: u128,
+    proof_bytes: u32,
+    security_bits: u32,
+    proof_digest: &'a str,
+    machine: Option<&'a str>,
+    repetition: u32,
+}
+
+// Carries the same configuration columns as the results, so that runs of a
+// job can be told apart.
+#[derive(Default, Serialize)]
+struct AllocRow<'a> {
+    prover: &'a str,
+    job_name: &'a str,
+    job_size: u32,
+    security_bits: u32,
+    threads: Option<usize>,
+    machine: Option<&'a str>,
+    repetition: u32,
+    phase: &'a str,
+    alloc_bytes: u64,
+    alloc_count: u64,
+    peak_heap_bytes: u64,
+}
+
+// One line of the JSON output; unlike the CSV it has room for nested data.
+#[derive(Serialize)]
+struct JsonRow<'a> {
+    prover: &'a str,
+    job_name: &'a str,
+    job_size: u32,
+    proof_duration_millisec: u128,
+    verify_duration_millisec: u128,
+    output_bytes: u32,
+    proof_bytes: u32,
+    security_bits: u32,
+    output_digest: &'a str,
+    proof_digest: &'a str,
+    threads: Option<usize>,
+    machine: Option<&'a str>,
+    repetition: u32,
+    discarded: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof_details: Option<&'a serde_json::Value>,
+}
+
+/// JSON lines file written next to the CSV output, one object per job.
+pub fn json_path(out_path: &Path) -> PathBuf {
+    out_path.with_extension("jsonl")
+}
+
+// Header line of a CSV file of `T` rows.
+fn csv_header<T: Serialize + Default>() -> String {
+    let mut csv = csv::Writer::from_writer(Vec::new());
+    csv.serialize(T::default()).expect("Could not serialize");
+    let data = String::from_utf8(csv.into_inner().unwrap()).unwrap();
+    String::from(data.lines().next().unwrap())
+}
+
+// Whether a CSV file of `T` rows already holds data, which it must then share
+// the columns of. Appending to a file written with other columns, such as by
+// an older version, would leave it unreadable.
+fn check_csv_header<T: Serialize + Default>(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut first = String::new();
+    BufReader::new(file)
+        .read_line(&mut first)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+    let found = first.trim_end_matches(['\r', '\n']);
+    if found.is_empty() {
+        return false;
+    }
+    let expected = csv_header::<T>();
+    if found != expected {
+        panic!(
+            "{} has the columns {}, not {}; write to a new file instead",
+            path.display(),
+            found,
+            expected
+        );
+    }
+    true
+}
+
+// Opens a CSV file of `T` rows for appending, writing the header only if it
+// is new.
+fn append_csv<T: Serialize + Default>(path: &Path) -> csv::Writer<File> {
+    let has_data = check_csv_header::<T>(path);
+    let file = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)
+        .unwrap();
+    csv::WriterBuilder::new()
+        .has_headers(!has_data)
+        .from_writer(file)
+}
+
+// The files every job's results are appended to.
+struct Outputs {
+    csv: csv::Writer<File>,
+    json: File,
+    // Per-phase heap usage goes to its own file, one row per phase.
+    allocations: Option<csv::Writer<File>>,
+    run_dir: PathBuf,
+}
+
+impl Outputs {
+    fn open(out_path: &Path) -> Self {
+        let run_dir = run_dir(out_path);
+        // Only opened once there is heap usage to record, but checked now so
+        // that a mismatch fails before any proving.
+        check_csv_header::<AllocRow>(&run_dir.join("allocations.csv"));
+        Outputs {
+            csv: append_csv::<CsvRow>(out_path),
+            json: OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(json_path(out_path))
+                .unwrap(),
+            allocations: None,
+            run_dir,
+        }
+    }
+
+    fn write(&mut self, metrics: &Metrics) {
+        self.csv
+            .serialize(CsvRow {
+                prover: &metrics.prover,
+                job_name: &metrics.job_name,
+                job_size: metrics.job_size,
+                proof_duration_millisec: metrics.proof_duration.as_millis(),
+                verify_duration_millisec: metrics.verify_duration.as_millis(),
+                proof_bytes: metrics.proof_bytes,
+                security_bits: metrics.security_bits,
+                output_digest: &metrics.output_digest,
+                proof_digest: &metrics.proof_digest,
+                threads: metrics.threads,
+                machine: metrics.machine.as_deref(),
+                repetition: metrics.repetition,
+                discarded: metrics.discarded,
+            })
+            .expect("Could not serialize");
+        self.csv.flush().expect("Could not flush");
+
+        let json = serde_json::to_string(&JsonRow {
+            prover: &metrics.prover,
+            job_name: &metrics.job_name,
+            job_size: metrics.job_size,
+            proof_duration_millisec: metrics.proof_duration.as_millis(),
+            verify_duration_millisec: metrics.verify_duration.as_millis(),
+            output_bytes: metrics.output_bytes,
+            proof_bytes: metrics.proof_bytes,
+            security_bits: metrics.security_bits,
+            output_digest: &metrics.output_digest,
+            proof_digest: &metrics.proof_digest,
+            threads: metrics.threads,
+            machine: metrics.machine.as_deref(),
+            repetition: metrics.repetition,
+            discarded: metrics.discarded,
+            proof_details: metrics.proof_details.as_ref(),
+        })
+        .expect("Could not serialize");
+        writeln!(self.json, "{}", json).expect("Could not write JSON output");
+
+        if !metrics.allocations.is_empty() {
+            let run_dir = &self.run_dir;
+            let allocations = self
+                .allocations
+                .get_or_insert_with(|| append_csv::<AllocRow>(&run_dir.join("allocations.csv")));
+            for (phase, stats) in &metrics.allocations {
+                allocations
+                    .serialize(AllocRow {
+                        prover: &metrics.prover,
+                        job_name: &metrics.job_name,
+                        job_size: metrics.job_size,
+                        security_bits: metrics.security_bits,
+                        threads: metrics.threads,
+                        machine: metrics.machine.as_deref(),
+                        repetition: metrics.repetition,
+                        phase,
+                        alloc_bytes: stats.bytes,
+                        alloc_count: stats.count,
+                        peak_heap_bytes: stats.peak_bytes,
+                    })
+                    .expect("Could not serialize");
+            }
+            allocations.flush().expect("Could not flush");
+        }
+    }
+}
+
+// Jobs with a thread count are set up and run on a worker of their own rayon
+// pool, so that the provers' parallel iterators use that pool too. Workers
+// get the main thread's usual stack size, since provers may recurse deeply.
+fn thread_pool(threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .stack_size(8 << 20)
+        .build()
+        .expect("Could not build thread pool")
+}
+
+fn run_job<B: Benchmark>(
+    spec: &B::Spec,
+    prover: &B::Prover,
+    #[cfg_attr(not(feature = "chrome-trace"), allow(unused_variables))] options: &RunOptions,
+    context: &JobContext,
+) -> Metrics {
+    #[cfg(feature = "chrome-trace")]
+    let trace = options
+        .trace
+        .then(|| trace::JobTrace::start(&context.run_dir.join("traces")));
+
+    let job_span = info_span!(
+        "job",
+        job_name = B::NAME,
+        job_size = B::job_size(spec),
+        prover = tracing::field::Empty,
+        threads = context.threads,
+        repetition = context.repetition
+    );
+    let entered = job_span.enter();
+
+    let phase = alloc::Phase::start();
+    let mut job = info_span!("setup").in_scope(|| B::new(spec, prover));
+    let setup_allocations = phase.finish();
+    job_span.record("prover", job.prover_name().as_str());
+
+    println!(
+        "Benchmarking:   {} {} {} (size {}, threads {}, repetition {})",
+        context.job_number,
+        B::NAME,
+        job.prover_name(),
+        B::job_size(spec),
+        context
+            .threads
+            .map_or(String::from("default"), |t| t.to_string()),
+        context.repetition
+    );
+
+    let mut metrics = job.run(options, context);
+    if alloc::installed() {
+        metrics.allocations.insert(0, ("setup", setup_allocations));
+    }
+    metrics.println("+ ");
+
+    drop(entered);
+    #[cfg(feature = "chrome-trace")]
+    if let Some(trace) = trace {
+        trace.finish(&job_file_name(&metrics, "json"));
+    }
+
+    metrics
+}
+
+// Proving the same spec again must give the same public output. Proofs may
+// legitimately differ, e.g. when the prover blinds with fresh randomness, but
+// identical proofs are worth knowing about too.
+fn check_determinism(samples: &[Metrics]) {
+    let Some(first) = samples.first() else {
+        return;
+    };
+    let outputs_match = samples
+        .iter()
+        .all(|m| m.output_digest == first.output_digest);
+    let proofs_match = samples.iter().all(|m| m.proof_digest == first.proof_digest);
+    println!(
+        "Determinism:    {} {} (size {}, {} runs): outputs {}, proofs {}",
+        first.job_name,
+        first.prover,
+        first.job_size,
+        samples.len(),
+        if outputs_match { "match" } else { "DIFFER" },
+        if proofs_match {
+            "byte-identical"
+        } else {
+            "differ"
+        }
+    );
+    assert!(
+        outputs_match,
+        "Nondeterministic output for {} {} (size {})",
+        first.job_name, first.prover, first.job_size
+    );
+}
+
+fn check_execution<B: Benchmark>(spec: &B::Spec, prover: &B::Prover) {
+    let mut job = B::new(spec, prover);
+
+    let start = Instant::now();
+    let g_output = info_span!("execute").in_scope(|| job.guest_execute());
+    let duration = start.elapsed();
+
+    let status = match job.host_compute() {
+        Some(h_output) => {
+            assert_eq!(g_output, h_output, "Guest and host outputs differ");
+            "output matches host"
+        }
+        None => "no host output to compare",
+    };
+    println!(
+        "Executed:       {} (size {}) in {:?}: {}",
+        B::NAME,
+        B::job_size(spec),
+        duration,
+        status
+    );
+}
+
+// Confines the calling thread, and the pools it goes on to build, to the
+// machine profile given in the options, if any.
+fn emulate_machine(options: &RunOptions) -> Option<Machine> {
+    let machine = options.machine_profile()?;
+    println!(
+        "Emulating machine {}: {} cores, {}",
+        machine.name,
+        machine.cores,
+        machine
+            .memory_gb
+            .map_or(String::from("unlimited memory"), |gb| format!("{} GB", gb))
+    );
+    machine.apply();
+    Some(machine)
 }
 
+// Identifies the runs of one job with one configuration in the output file.
+#[derive(Deserialize, PartialEq, Eq, Hash)]
+struct RunKey {
+    prover: String,
+    job_name: String,
+    job_size: u32,
+    security_bits: Option<u32>,
+    threads: Option<usize>,
+    machine: Option<String>,
+}
+
+impl RunKey {
+    // Whether `run`, read from the output file, is a run of this job and
+    // configuration. A security level unknown before proving matches any.
+    fn matches(&self, run: &RunKey) -> bool {
+        (&self.prover, &self.job_name, self.job_size) == (&run.prover, &run.job_name, run.job_size)
+            && (self.threads, &self.machine) == (run.threads, &run.machine)
+            && (self.security_bits.is_none() || self.security_bits == run.security_bits)
+    }
+}
+
+// Number of results already in the output file of every job and
+// configuration. A job's repetitions are written together once its outliers
+// are replaced, so an interrupted run leaves none of the job behind.
+fn completed_runs(out_path: &Path) -> HashMap<RunKey, u32> {
+    let mut counts = HashMap::new();
+    if !check_csv_header::<CsvRow>(out_path) {
+        return counts;
+    }
+    let mut rdr = csv::Reader::from_path(out_path)
+        .unwrap_or_else(|e| panic!("Could not read {}: {}", out_path.display(), e));
+    for key in rdr.deserialize::<RunKey>() {
+        let key = key.unwrap_or_else(|e| panic!("Invalid row in {}: {}", out_path.display(), e));
+        *counts.entry(key).or_default() += 1;
+    }
+    counts
+}
+
+/// Runs every job `repetitions` times, then replaces the repetitions whose
+/// proof time is an outlier (by their median absolute deviation) with new
+/// runs, until none is left or `max_reruns` runs were spent. Only the retained
+/// samples are written out, each recording how many were discarded. With
+/// `resume`, only the repetitions missing from `out_path` run, numbered after
+/// those there.
 pub fn run_jobs<B: Benchmark>(
-    out_path: &PathBuf,
+    out_path: &Path,
     specs: &Vec<B::Spec>,
     provers: &Vec<B::Prover>,
-) -> Vec<Metrics> {
+    options: &RunOptions,
+) -> Vec<Metrics>
+where
+    B::Spec: Sync,
+    B::Prover: Sync,
+{
+    let machine = emulate_machine(options);
+
+    if options.check == Some(Check::Execute) {
+        // Execution does not depend on the prover, so any one will do
+        if let Some(prover) = provers.first() {
+            for spec in specs {
+                check_execution::<B>(spec, prover);
+            }
+        }
+        return Vec::new();
+    }
+
+    let mut repetitions = options.repetitions.unwrap_or(1);
+    if options.check == Some(Check::Determinism) {
+        repetitions = repetitions.max(2);
+    }
+    let max_reruns = options.max_reruns.unwrap_or(repetitions);
+    // On an emulated machine rayon's default would size its pool after the
+    // host's cores, not the machine's
+    let thread_counts: Vec<Option<usize>> = if options.threads.is_empty() {
+        vec![machine.as_ref().map(|m| m.cores)]
+    } else {
+        options.threads.iter().copied().map(Some).collect()
+    };
+
     println!(
         "Running {} jobs; saving output to {}",
-        specs.len(),
+        specs.len() * provers.len() * thread_counts.len() * repetitions as usize,
         out_path.display()
     );
 
-    let mut out = {
-        let out_file_exists = Path::new(out_path).exists();
-        let out_file = OpenOptions::new()
-            .write(true)
-            .append(true)
-            .create(true)
-            .open(out_path)
-            .unwrap();
-        csv::WriterBuilder::new()
-            .has_headers(!out_file_exists)
-            .from_writer(out_file)
+    let completed = if options.resume {
+        completed_runs(out_path)
+    } else {
+        HashMap::new()
     };
-
+    let mut outputs = Outputs::open(out_path);
     let mut all_metrics: Vec<Metrics> = Vec::new();
+    let mut job_number = 0;
 
     for spec in specs {
         for prover in provers {
-            let mut job = B::new(spec, prover);
-            let job_number = all_metrics.len();
+            for threads in &thread_counts {
+                let key = RunKey {
+                    prover: prover.to_string(),
+                    job_name: String::from(B::NAME),
+                    job_size: B::job_size(spec),
+                    security_bits: B::prover_security_bits(prover),
+                    threads: *threads,
+                    machine: machine.as_ref().map(|m| m.name.clone()),
+                };
+                let done: u32 = completed
+                    .iter()
+                    .filter(|(run, _)| key.matches(run))
+                    .map(|(_, count)| count)
+                    .sum();
+                if done >= repetitions {
+                    println!(
+                        "Skipping:       {} {} (size {}): {} results already in {}",
+                        key.job_name,
+                        key.prover,
+                        key.job_size,
+                        done,
+                        out_path.display()
+                    );
+                    continue;
+                }
+
+                let pool = threads.map(thread_pool);
+                let mut next_repetition = done;
+                let mut run = |count: u32| -> Vec<Metrics> {
+                    (0..count)
+                        .map(|_| {
+                            let context = JobContext {
+                                run_dir: run_dir(out_path),
+                                job_number,
+                                threads: *threads,
+                                machine: machine.as_ref().map(|m| m.name.clone()),
+                                repetition: next_repetition,
+                            };
+                            job_number += 1;
+                            next_repetition += 1;
+                            let run = || run_job::<B>(spec, prover, options, &context);
+                            match &pool {
+                                Some(pool) => pool.install(run),
+                                None => run(),
+                            }
+                        })
+                        .collect()
+                };
+
+                let mut samples = run(repetitions - done);
+                let mut discarded = 0;
+                loop {
+                    let durations: Vec<f64> = samples
+                        .iter()
+                        .map(|m| m.proof_duration.as_secs_f64())
+                        .collect();
+                    let mut outliers = outliers::outliers(&durations);
+                    outliers.truncate((max_reruns - discarded) as usize);
+                    if outliers.is_empty() {
+                        break;
+                    }
+
+                    // Remove from the back so that the other indices stay valid
+                    outliers.sort_unstable_by(|a, b| b.cmp(a));
+                    for index in &outliers {
+                        let outlier = samples.remove(*index);
+                        println!(
+                            "Discarding outlier: {} {} (size {}, repetition {}) proved in {:?}",
+                            outlier.job_name,
+                            outlier.prover,
+                            outlier.job_size,
+                            outlier.repetition,
+                            outlier.proof_duration
+                        );
+                    }
+                    discarded += outliers.len() as u32;
+                    samples.extend(run(outliers.len() as u32));
+                }
+
+                for metrics in &mut samples {
+                    metrics.discarded = discarded;
+                    outputs.write(metrics);
+                }
+                if options.check == Some(Check::Determinism) {
+                    check_determinism(&samples);
+                }
+                all_metrics.extend(samples);
+            }
+        }
+    }
+
+    all_metrics
+}
+
+/// Verifies every proof of `B` saved under `dir` with `--save-proofs`, timing
+/// verification only, and appends one row per verification to `out_path`.
+pub fn verify_jobs<B: Benchmark>(out_path: &Path, dir: &Path, options: &RunOptions)
+where
+    B::Artifact: Sync,
+{
+    let saved = artifact::load::<B::Artifact>(dir, B::NAME);
+    if saved.is_empty() {
+        return;
+    }
+    let machine = emulate_machine(options);
+    let pool = machine.as_ref().map(|m| thread_pool(m.cores));
+    let repetitions = options.repetitions.unwrap_or(1);
 
+    println!(
+        "Verifying {} proofs; saving output to {}",
+        saved.len() * repetitions as usize,
+        out_path.display()
+    );
+
+    let mut csv = append_csv::<VerifyRow>(out_path);
+    for (job_number, proof) in saved.iter().enumerate() {
+        for repetition in 0..repetitions {
             println!(
-                "Benchmarking:   {} {} {}",
-                job_number,
-                B::NAME,
-                job.prover_name()
+                "Verifying:      {} {} {} (size {}, repetition {})",
+                job_number, proof.job_name, proof.prover, proof.job_size, repetition
+            );
+
+            let verify = || {
+                let start = Instant::now();
+                let verified =
+                    info_span!("verify").in_scope(|| B::verify_artifact(&proof.artifact));
+                (verified, start.elapsed())
+            };
+            let (verified, verify_duration) = match &pool {
+                Some(pool) => pool.install(verify),
+                None => verify(),
+            };
+            assert!(
+                verified,
+                "Saved proof of {} {} (size {}) failed verification",
+                proof.job_name, proof.prover, proof.job_size
             );
+            info!("+ verify_duration:    {:?}", verify_duration);
 
-            let job_metrics = job.run();
-            job_metrics.println("+ ");
-            let prover = B::prover_name(&job);
-            out.serialize(CsvRow {
-                prover: &prover,
-                job_name: &job_metrics.job_name,
-                job_size: job_metrics.job_size,
-                proof_duration_millisec: job_metrics.proof_duration.as_millis(),
-                verify_duration_millisec: job_metrics.verify_duration.as_millis(),
-                proof_bytes: job_metrics.proof_bytes,
+            csv.serialize(VerifyRow {
+                prover: &proof.prover,
+                job_name: &proof.job_name,
+                job_size: proof.job_size,
+                verify_duration_millisec: verify_duration.as_millis(),
+                proof_bytes: proof.proof_bytes,
+                security_bits: proof.security_bits,
+                proof_digest: &proof.proof_digest,
+                machine: machine.as_ref().map(|m| m.name.as_str()),
+                repetition,
             })
             .expect("Could not serialize");
-            out.flush().expect("Could not flush");
+            csv.flush().expect("Could not flush");
+        }
+    }
+}
 
-            all_metrics.push(job_metrics);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn row(repetition: u32) -> VerifyRow<'static> {
+        VerifyRow {
+            prover: "mock",
+            repetition,
+            ..Default::default()
         }
     }
 
-    out.flush().expect("Could not flush");
+    #[test]
+    fn appends_with_a_single_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("verify.csv");
+        for repetition in 0..2 {
+            let mut csv = append_csv::<VerifyRow>(&path);
+            csv.serialize(row(repetition)).unwrap();
+        }
 
-    all_metrics
+        let data = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = data.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], csv_header::<VerifyRow>());
+    }
+
+    #[test]
+    fn writes_a_header_to_empty_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("verify.csv");
+        fs::write(&path, "").unwrap();
+        append_csv::<VerifyRow>(&path).serialize(row(0)).unwrap();
+
+        let data = fs::read_to_string(&path).unwrap();
+        assert!(data.starts_with("prover,job_name,job_size,"));
+    }
+
+    #[test]
+    #[should_panic(expected = "write to a new file instead")]
+    fn rejects_files_with_other_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("verify.csv");
+        fs::write(&path, "prover,job_name,job_size\nmock,fib,1\n").unwrap();
+        append_csv::<VerifyRow>(&path);
+    }
+
+    #[test]
+    fn file_stems_tell_machines_apart() {
+        let mut metrics = Metrics::new(String::from("fib"), 10, String::from("mock"));
+        metrics.threads = Some(2);
+        metrics.repetition = 1;
+        assert_eq!(job_file_stem(&metrics), "fib_10_mock_t2_r1");
+
+        metrics.machine = Some(String::from("e2-standard-4"));
+        assert_eq!(job_file_stem(&metrics), "fib_10_mock_e2-standard-4_t2_r1");
+    }
 }
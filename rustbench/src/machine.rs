@@ -0,0 +1,111 @@
+// Emulating a smaller machine on a bigger one, so that the shapes of the GCP
+// batch runs can be approximated without launching them.
+
+use serde::Deserialize;
+
+/// Hardware a job is confined to: the first `cores` CPUs the process may run
+/// on, and at most `memory_gb` of heap.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Machine {
+    /// Set from the profile's name, not read from the suite
+    #[serde(skip)]
+    pub name: String,
+    pub cores: usize,
+    /// Unlimited when omitted
+    pub memory_gb: Option<f64>,
+}
+
+// GCP machine types the benchmarks used to be run on: name, vCPUs, memory
+const PROFILES: &[(&str, usize, f64)] = &[
+    ("e2-highmem-2", 2, 16.0),
+    ("e2-standard-4", 4, 16.0),
+    ("e2-highmem-4", 4, 32.0),
+    ("e2-standard-8", 8, 32.0),
+];
+
+impl Machine {
+    /// The built-in profile called `name`, if any.
+    pub fn builtin(name: &str) -> Option<Self> {
+        PROFILES
+            .iter()
+            .find(|(n, _, _)| *n == name)
+            .map(|&(name, cores, memory_gb)| Machine {
+                name: String::from(name),
+                cores,
+                memory_gb: Some(memory_gb),
+            })
+    }
+
+    pub fn builtin_names() -> Vec<&'static str> {
+        PROFILES.iter().map(|(name, _, _)| *name).collect()
+    }
+
+    /// Confines the calling thread, and every thread it spawns from now on,
+    /// to the machine's cores, and caps the heap of the whole process.
+    ///
+    /// Cores are taken in order from those the process may already use, so
+    /// on a machine with SMT a profile may get sibling hyperthreads, much
+    /// like cloud vCPUs. The memory cap is a soft `RLIMIT_DATA`: an
+    /// allocation past it aborts the process, as running out of memory
+    /// would. Applying the same machine again changes nothing.
+    #[cfg(target_os = "linux")]
+    pub fn apply(&self) {
+        // SAFETY: `cpu_set_t` is plain data, and the calls only read and
+        // write the set and rlimit they are given.
+        unsafe {
+            let mut available: libc::cpu_set_t = std::mem::zeroed();
+            let size = std::mem::size_of::<libc::cpu_set_t>();
+            if libc::sched_getaffinity(0, size, &mut available) != 0 {
+                panic!(
+                    "Could not get CPU affinity: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            let cpus: Vec<usize> = (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &available))
+                .collect();
+            assert!(
+                self.cores > 0 && self.cores <= cpus.len(),
+                "Machine {} needs {} cores, but only {} are available",
+                self.name,
+                self.cores,
+                cpus.len()
+            );
+
+            let mut set: libc::cpu_set_t = std::mem::zeroed();
+            for &cpu in &cpus[..self.cores] {
+                libc::CPU_SET(cpu, &mut set);
+            }
+            if libc::sched_setaffinity(0, size, &set) != 0 {
+                panic!(
+                    "Could not set CPU affinity: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+
+            if let Some(memory_gb) = self.memory_gb {
+                let mut limit: libc::rlimit = std::mem::zeroed();
+                if libc::getrlimit(libc::RLIMIT_DATA, &mut limit) != 0 {
+                    panic!(
+                        "Could not get memory limit: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+                let bytes = (memory_gb * (1u64 << 30) as f64) as libc::rlim_t;
+                limit.rlim_cur = bytes.min(limit.rlim_max);
+                if libc::setrlimit(libc::RLIMIT_DATA, &limit) != 0 {
+                    panic!(
+                        "Could not set memory limit: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn apply(&self) {
+        panic!("Machine profiles are only supported on Linux");
+    }
+}
@@ -0,0 +1,105 @@
+// Detection of outlier samples among the repetitions of a job, e.g. a run
+// that took twice as long because a cloud VM was briefly starved of CPU.
+
+/// Modified z-score above which a sample is an outlier, as recommended by
+/// Iglewicz and Hoaglin.
+pub const THRESHOLD: f64 = 3.5;
+
+// With fewer samples there is no telling which one is off.
+const MIN_SAMPLES: usize = 3;
+
+// Scales the median absolute deviation to a standard deviation for normally
+// distributed samples.
+const MAD_SCALE: f64 = 0.6745;
+
+fn median(values: &mut [f64]) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[mid]
+    } else {
+        (values[mid - 1] + values[mid]) / 2.0
+    }
+}
+
+/// Indices of the outliers among `samples`, most extreme first.
+///
+/// A sample is an outlier when its modified z-score, computed from the median
+/// and the median absolute deviation (MAD), exceeds [`THRESHOLD`]. The MAD is
+/// floored at 1% of the median, so that near-identical samples do not turn a
+/// millisecond of jitter into an outlier.
+pub fn outliers(samples: &[f64]) -> Vec<usize> {
+    if samples.len() < MIN_SAMPLES {
+        return Vec::new();
+    }
+
+    let median = median(&mut samples.to_vec());
+    let mut deviations: Vec<f64> = samples.iter().map(|s| (s - median).abs()).collect();
+    let mad = self::median(&mut deviations).max(median.abs() * 0.01);
+    if mad == 0.0 {
+        return Vec::new();
+    }
+
+    let mut scored: Vec<(usize, f64)> = samples
+        .iter()
+        .map(|s| MAD_SCALE * (s - median).abs() / mad)
+        .enumerate()
+        .filter(|(_, score)| *score > THRESHOLD)
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().map(|(index, _)| index).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_a_slow_sample() {
+        assert_eq!(outliers(&[10.0, 10.1, 9.9, 20.0, 10.05]), [3]);
+    }
+
+    #[test]
+    fn flags_fast_samples_too() {
+        assert_eq!(outliers(&[10.0, 10.1, 9.9, 1.0, 10.05]), [3]);
+    }
+
+    #[test]
+    fn orders_by_distance_from_the_median() {
+        assert_eq!(
+            outliers(&[30.0, 10.0, 10.1, 9.9, 10.0, 20.0, 10.05]),
+            [0, 5]
+        );
+    }
+
+    #[test]
+    fn keeps_ordinary_spread() {
+        assert!(outliers(&[10.0, 11.0, 12.0, 13.0, 14.0]).is_empty());
+    }
+
+    #[test]
+    fn ignores_jitter_between_near_identical_samples() {
+        assert!(outliers(&[100.0, 100.0, 100.0, 100.5]).is_empty());
+    }
+
+    #[test]
+    fn flags_an_outlier_among_identical_samples() {
+        assert_eq!(outliers(&[100.0, 100.0, 100.0, 200.0]), [3]);
+    }
+
+    #[test]
+    fn needs_three_samples() {
+        assert!(outliers(&[1.0, 100.0]).is_empty());
+    }
+
+    #[test]
+    fn ignores_all_zero_samples() {
+        assert!(outliers(&[0.0, 0.0, 0.0]).is_empty());
+    }
+
+    #[test]
+    fn median_of_even_and_odd_counts() {
+        assert_eq!(median(&mut [3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&mut [4.0, 1.0, 3.0, 2.0]), 2.5);
+    }
+}
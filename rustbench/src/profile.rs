@@ -0,0 +1,54 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use pprof::protos::Message;
+use pprof::{ProfilerGuard, ProfilerGuardBuilder};
+
+// Samples per second. Slightly off a round number so that sampling does not
+// fall into lockstep with periodic work in the prover.
+const FREQUENCY: i32 = 997;
+
+/// Samples every thread of the process while proving one job.
+pub struct JobProfile<'a> {
+    guard: ProfilerGuard<'a>,
+}
+
+impl<'a> JobProfile<'a> {
+    pub fn start() -> Self {
+        let guard = ProfilerGuardBuilder::default()
+            .frequency(FREQUENCY)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .expect("Could not start profiler");
+        JobProfile { guard }
+    }
+
+    /// Writes `<file_stem>.svg` (flamegraph) and `<file_stem>.pb` (pprof
+    /// protobuf) into `dir`.
+    pub fn finish(self, dir: &Path, file_stem: &str) {
+        let report = self
+            .guard
+            .report()
+            .build()
+            .expect("Could not build profile");
+        fs::create_dir_all(dir).expect("Could not create profile directory");
+
+        let svg = dir.join(format!("{}.svg", file_stem));
+        let file = File::create(&svg).expect("Could not create flamegraph");
+        report.flamegraph(file).expect("Could not write flamegraph");
+
+        let pb = dir.join(format!("{}.pb", file_stem));
+        let mut bytes = Vec::new();
+        report
+            .pprof()
+            .expect("Could not convert profile")
+            .encode(&mut bytes)
+            .expect("Could not encode profile");
+        File::create(&pb)
+            .and_then(|mut f| f.write_all(&bytes))
+            .expect("Could not write profile");
+
+        println!("Profile written to {} and {}", svg.display(), pb.display());
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::Machine;
+
+/// A benchmark suite described in a TOML file, shared by every host binary:
+///
+/// ```toml
+/// repetitions = 3
+/// threads = [8, 16]
+/// machine = "small"
+///
+/// [machines.small]
+/// cores = 4
+/// memory_gb = 8
+///
+/// [miden]
+/// security = [96, 128]
+/// benches.iter_sha2.sizes = [10, 100]
+///
+/// [risczero]
+/// provers = ["CpuSHA256"]
+/// benches.fact = { sizes = [5, 10], repetitions = 1 }
+/// ```
+///
+/// Each host only reads the table named after its system, and only runs the
+/// benches listed there.
+#[derive(Debug, Default, Deserialize)]
+pub struct Suite {
+    pub repetitions: Option<u32>,
+    pub max_reruns: Option<u32>,
+    pub threads: Option<Vec<usize>>,
+    /// Machine profile to emulate, see [`Machine`]
+    pub machine: Option<String>,
+    /// Machine profiles defined by the suite, alongside the built-in ones
+    #[serde(default)]
+    pub machines: BTreeMap<String, Machine>,
+    #[serde(flatten)]
+    pub systems: BTreeMap<String, SystemSuite>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SystemSuite {
+    /// Provers to run; every available prover when omitted
+    pub provers: Option<Vec<String>>,
+    /// Security levels to prove at; the system's default when omitted
+    pub security: Option<Vec<u32>>,
+    #[serde(default)]
+    pub benches: BTreeMap<String, BenchSuite>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BenchSuite {
+    /// Job sizes; the bench's defaults when omitted
+    pub sizes: Option<Vec<u32>>,
+    pub repetitions: Option<u32>,
+    pub max_reruns: Option<u32>,
+    pub threads: Option<Vec<usize>>,
+}
+
+impl Suite {
+    pub fn load(path: &Path) -> Self {
+        let text = fs::read_to_string(path)
+            .unwrap_or_else(|e| panic!("Could not read suite {}: {}", path.display(), e));
+        toml::from_str(&text).unwrap_or_else(|e| panic!("Invalid suite {}: {}", path.display(), e))
+    }
+
+    pub fn system(&self, name: &str) -> Option<&SystemSuite> {
+        self.systems.get(name)
+    }
+
+    /// Whether `bench` of `system` is listed, and so run by its host.
+    pub fn selects(&self, system: &str, bench: &str) -> bool {
+        matches!(self.system(system), Some(s) if s.benches.contains_key(bench))
+    }
+
+    /// The machine profile called `name`, preferring the suite's own.
+    pub fn machine(&self, name: &str) -> Option<Machine> {
+        match self.machines.get(name) {
+            Some(machine) => Some(Machine {
+                name: String::from(name),
+                ..machine.clone()
+            }),
+            None => Machine::builtin(name),
+        }
+    }
+}
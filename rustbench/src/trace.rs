@@ -0,0 +1,45 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tracing::subscriber::DefaultGuard;
+use tracing_chrome::{ChromeLayerBuilder, FlushGuard};
+use tracing_subscriber::prelude::*;
+
+// The prover name is only known once the job has been set up, so the trace
+// is written to a temporary file and renamed when the job is done.
+const CURRENT: &str = ".current.json";
+
+/// Records the spans of one job into a Chrome trace file. Only spans entered
+/// on the thread running the job are recorded.
+pub struct JobTrace {
+    dir: PathBuf,
+    default: DefaultGuard,
+    flush: FlushGuard,
+}
+
+impl JobTrace {
+    pub fn start(dir: &Path) -> Self {
+        fs::create_dir_all(dir).expect("Could not create trace directory");
+
+        let (layer, flush) = ChromeLayerBuilder::new()
+            .file(dir.join(CURRENT))
+            .include_args(true)
+            .build();
+        let default = tracing::subscriber::set_default(tracing_subscriber::registry().with(layer));
+
+        JobTrace {
+            dir: dir.to_path_buf(),
+            default,
+            flush,
+        }
+    }
+
+    pub fn finish(self, file_name: &str) {
+        drop(self.default);
+        drop(self.flush);
+
+        let path = self.dir.join(file_name);
+        fs::rename(self.dir.join(CURRENT), &path).expect("Could not write trace");
+        println!("Trace written to {}", path.display());
+    }
+}
@@ -0,0 +1,47 @@
+// Runs with the counting allocator installed, as hosts built with
+// `count-alloc` do, in a test binary of its own. Not every mock helper is
+// used here.
+#[allow(dead_code)]
+mod mock;
+
+use std::collections::{BTreeSet, HashMap};
+
+use mock::{Mock, MockSpec};
+use rustbench::{run_jobs, RunOptions};
+
+#[global_allocator]
+static ALLOC: rustbench::CountingAlloc = rustbench::CountingAlloc;
+
+#[test]
+fn records_the_run_with_heap_usage() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let options = RunOptions {
+        repetitions: Some(2),
+        threads: vec![1, 2],
+        ..Default::default()
+    };
+
+    run_jobs::<Mock>(
+        &out,
+        &vec![MockSpec::new(1)],
+        &vec![String::from("mock")],
+        &options,
+    );
+
+    let rows: Vec<HashMap<String, String>> =
+        csv::Reader::from_path(dir.path().join("allocations.csv"))
+            .unwrap()
+            .deserialize()
+            .map(Result::unwrap)
+            .collect();
+    assert!(rows.iter().all(|r| r["security_bits"] == "100"));
+    let runs: BTreeSet<(&str, &str)> = rows
+        .iter()
+        .map(|r| (r["threads"].as_str(), r["repetition"].as_str()))
+        .collect();
+    assert_eq!(
+        runs,
+        BTreeSet::from([("1", "0"), ("1", "1"), ("2", "0"), ("2", "1")])
+    );
+}
@@ -0,0 +1,154 @@
+// A Benchmark that proves nothing, so that the runner can be tested in
+// milliseconds. Every spec carries its own call counter, so that behaviour
+// can vary from one repetition to the next without global state.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use rustbench::Benchmark;
+
+#[derive(Clone, Default)]
+pub struct MockSpec {
+    pub size: u32,
+    /// Proving time of each successive run, cycling; instant when empty
+    pub prove_delays: Vec<Duration>,
+    /// Host and guest disagree on the output
+    pub mismatch: bool,
+    /// The public output changes from one run to the next
+    pub nondeterministic: bool,
+    /// Proofs fail verification right after proving
+    pub fail_verify: bool,
+    /// Proofs pass verification after proving, but not once saved
+    pub corrupt_saved: bool,
+    pub calls: Arc<AtomicUsize>,
+}
+
+impl MockSpec {
+    pub fn new(size: u32) -> Self {
+        MockSpec {
+            size,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_delays(size: u32, millis: &[u64]) -> Self {
+        MockSpec {
+            prove_delays: millis.iter().copied().map(Duration::from_millis).collect(),
+            ..MockSpec::new(size)
+        }
+    }
+
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+pub struct Mock {
+    spec: MockSpec,
+    prover: String,
+    call: usize,
+}
+
+pub const PROOF_BYTES: u32 = 16;
+
+impl Benchmark for Mock {
+    const NAME: &'static str = "mock";
+    type Spec = MockSpec;
+    type ComputeOut = u32;
+    type ProofType = Vec<u8>;
+    type Prover = String;
+    type Artifact = (u32, Vec<u8>, bool);
+
+    fn prover_name(&self) -> String {
+        self.prover.clone()
+    }
+
+    fn job_size(spec: &Self::Spec) -> u32 {
+        spec.size
+    }
+
+    fn output_size_bytes(_output: &Self::ComputeOut, _proof: &Self::ProofType) -> u32 {
+        4
+    }
+
+    fn proof_size_bytes(proof: &Self::ProofType) -> u32 {
+        proof.len() as u32
+    }
+
+    fn public_output(output: &Self::ComputeOut, _proof: &Self::ProofType) -> Vec<u8> {
+        output.to_le_bytes().to_vec()
+    }
+
+    fn proof_to_bytes(proof: &Self::ProofType) -> Vec<u8> {
+        proof.clone()
+    }
+
+    fn security_bits(_proof: &Self::ProofType) -> u32 {
+        100
+    }
+
+    fn prover_security_bits(_prover: &Self::Prover) -> Option<u32> {
+        Some(100)
+    }
+
+    fn proof_details(proof: &Self::ProofType) -> Option<serde_json::Value> {
+        Some(serde_json::json!({ "len": proof.len() }))
+    }
+
+    fn new(spec: &Self::Spec, prover: &Self::Prover) -> Self {
+        Mock {
+            spec: spec.clone(),
+            prover: prover.clone(),
+            call: spec.calls.fetch_add(1, Ordering::SeqCst),
+        }
+    }
+
+    fn spec(&self) -> &Self::Spec {
+        &self.spec
+    }
+
+    fn host_compute(&mut self) -> Option<Self::ComputeOut> {
+        Some(self.expected_output())
+    }
+
+    fn guest_compute(&mut self) -> (Self::ComputeOut, Self::ProofType) {
+        let delays = &self.spec.prove_delays;
+        if !delays.is_empty() {
+            std::thread::sleep(delays[self.call % delays.len()]);
+        }
+
+        let output = if self.spec.mismatch {
+            self.expected_output() + 1
+        } else {
+            self.expected_output()
+        };
+        (output, vec![self.spec.size as u8; PROOF_BYTES as usize])
+    }
+
+    fn guest_execute(&mut self) -> Self::ComputeOut {
+        self.guest_compute().0
+    }
+
+    fn verify_proof(&self, _output: &Self::ComputeOut, _proof: &Self::ProofType) -> bool {
+        !self.spec.fail_verify
+    }
+
+    fn artifact(&self, output: &Self::ComputeOut, proof: &Self::ProofType) -> Self::Artifact {
+        (*output, proof.clone(), self.spec.corrupt_saved)
+    }
+
+    fn verify_artifact(artifact: &Self::Artifact) -> bool {
+        !artifact.2
+    }
+}
+
+impl Mock {
+    fn expected_output(&self) -> u32 {
+        if self.spec.nondeterministic {
+            self.spec.size + self.call as u32
+        } else {
+            self.spec.size
+        }
+    }
+}
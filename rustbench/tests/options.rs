@@ -0,0 +1,156 @@
+use std::fs;
+
+use rustbench::{Machine, RunOptions, Suite};
+
+const SUITE: &str = r#"
+repetitions = 3
+threads = [8, 16]
+machine = "small"
+
+[machines.small]
+cores = 2
+memory_gb = 4
+
+[miden]
+security = [96, 128]
+benches.iter_sha2.sizes = [10, 100]
+benches.iter_blake3 = { repetitions = 1, threads = [4] }
+
+[risczero]
+provers = ["CpuSHA256"]
+benches.fact.sizes = [5]
+"#;
+
+fn suite() -> Suite {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("suite.toml");
+    fs::write(&path, SUITE).unwrap();
+    Suite::load(&path)
+}
+
+#[test]
+fn loads_systems_and_benches() {
+    let suite = suite();
+    let miden = suite.system("miden").unwrap();
+    assert_eq!(miden.security, Some(vec![96, 128]));
+    assert_eq!(miden.benches.len(), 2);
+    let risczero = suite.system("risczero").unwrap();
+    assert_eq!(risczero.provers, Some(vec![String::from("CpuSHA256")]));
+    assert!(suite.system("other").is_none());
+}
+
+#[test]
+#[should_panic(expected = "Invalid suite")]
+fn rejects_unknown_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("suite.toml");
+    fs::write(&path, "[miden]\nbenches.iter_sha2.size = [10]\n").unwrap();
+    Suite::load(&path);
+}
+
+#[test]
+fn fills_bench_options_from_the_suite() {
+    let suite = suite();
+    let options = RunOptions::default()
+        .for_bench(Some(&suite), "miden", "iter_sha2")
+        .unwrap();
+    assert_eq!(options.sizes_or(&[1]), [10, 100]);
+    assert_eq!(options.repetitions, Some(3));
+    assert_eq!(options.threads, [8, 16]);
+
+    // Per-bench settings take precedence over suite-wide ones
+    let options = RunOptions::default()
+        .for_bench(Some(&suite), "miden", "iter_blake3")
+        .unwrap();
+    assert_eq!(options.sizes_or(&[1]), [1]);
+    assert_eq!(options.repetitions, Some(1));
+    assert_eq!(options.threads, [4]);
+}
+
+#[test]
+fn command_line_overrides_the_suite() {
+    let suite = suite();
+    let cli = RunOptions {
+        sizes: vec![7],
+        repetitions: Some(5),
+        threads: vec![2],
+        security: Some(128),
+        ..Default::default()
+    };
+    let options = cli.for_bench(Some(&suite), "miden", "iter_sha2").unwrap();
+    assert_eq!(options.sizes_or(&[1]), [7]);
+    assert_eq!(options.repetitions, Some(5));
+    assert_eq!(options.threads, [2]);
+    assert_eq!(cli.security_levels(Some(&suite), "miden"), [Some(128)]);
+}
+
+#[test]
+fn skips_benches_left_out_of_the_suite() {
+    let suite = suite();
+    let options = RunOptions::default();
+    assert!(options
+        .for_bench(Some(&suite), "miden", "iter_rescue_prime")
+        .is_none());
+    assert!(options.for_bench(Some(&suite), "other", "fact").is_none());
+    assert!(options
+        .for_bench(None, "miden", "iter_rescue_prime")
+        .is_some());
+}
+
+#[test]
+fn selects_the_benches_the_hosts_run() {
+    let suite = suite();
+    assert!(suite.selects("miden", "iter_sha2"));
+    assert!(suite.selects("risczero", "fact"));
+    assert!(!suite.selects("miden", "iter_rescue_prime"));
+    assert!(!suite.selects("risczero", "iter_sha2"));
+    assert!(!suite.selects("other", "fact"));
+}
+
+#[test]
+fn security_levels_default_to_the_system() {
+    let suite = suite();
+    let options = RunOptions::default();
+    assert_eq!(
+        options.security_levels(Some(&suite), "miden"),
+        [Some(96), Some(128)]
+    );
+    assert_eq!(options.security_levels(Some(&suite), "risczero"), [None]);
+    assert_eq!(options.security_levels(None, "miden"), [None]);
+}
+
+#[test]
+fn resolves_machine_profiles() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("suite.toml");
+    fs::write(&path, SUITE).unwrap();
+
+    let from_suite = RunOptions {
+        suite: Some(path.clone()),
+        ..Default::default()
+    };
+    let small = from_suite.machine_profile().unwrap();
+    assert_eq!(
+        (small.name.as_str(), small.cores, small.memory_gb),
+        ("small", 2, Some(4.0))
+    );
+
+    // The command line picks a built-in profile over the suite's choice
+    let builtin = RunOptions {
+        machine: Some(String::from("e2-standard-4")),
+        ..from_suite
+    };
+    assert_eq!(builtin.machine_profile(), Machine::builtin("e2-standard-4"));
+    assert_eq!(builtin.machine_profile().unwrap().cores, 4);
+    assert!(RunOptions::default().machine_profile().is_none());
+}
+
+#[test]
+#[should_panic(expected = "Unknown machine")]
+fn rejects_unknown_machines() {
+    let options = RunOptions {
+        machine: Some(String::from("e2-huge")),
+        ..Default::default()
+    };
+    options.machine_profile();
+}
@@ -0,0 +1,532 @@
+mod mock;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use mock::{Mock, MockSpec, PROOF_BYTES};
+use rustbench::{json_path, run_jobs, verify_jobs, Check, RunOptions};
+
+fn read_csv(path: &Path) -> Vec<HashMap<String, String>> {
+    csv::Reader::from_path(path)
+        .unwrap()
+        .deserialize()
+        .map(Result::unwrap)
+        .collect()
+}
+
+fn read_json(path: &Path) -> Vec<serde_json::Value> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn provers() -> Vec<String> {
+    vec![String::from("mock")]
+}
+
+#[test]
+fn times_proving_and_records_metrics() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+
+    let metrics = run_jobs::<Mock>(
+        &out,
+        &vec![MockSpec::with_delays(3, &[20])],
+        &provers(),
+        &RunOptions::default(),
+    );
+
+    assert_eq!(metrics.len(), 1);
+    let m = &metrics[0];
+    assert_eq!(
+        (m.job_name.as_str(), m.job_size, m.prover.as_str()),
+        ("mock", 3, "mock")
+    );
+    assert!(m.proof_duration >= Duration::from_millis(20));
+    assert!(m.proof_duration < Duration::from_secs(2));
+    assert!(m.verify_duration < Duration::from_millis(20));
+    assert_eq!(m.proof_bytes, PROOF_BYTES);
+    assert_eq!(m.output_bytes, 4);
+    assert_eq!(m.security_bits, 100);
+    assert_eq!(m.output_digest.len(), 64);
+    assert_eq!(m.proof_digest.len(), 64);
+    // Allocations are only recorded with CountingAlloc installed
+    assert!(m.allocations.is_empty());
+}
+
+#[test]
+fn writes_csv_and_json_rows() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let specs = vec![MockSpec::new(1), MockSpec::new(2)];
+
+    run_jobs::<Mock>(&out, &specs, &provers(), &RunOptions::default());
+
+    let rows = read_csv(&out);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["job_name"], "mock");
+    assert_eq!(rows[0]["job_size"], "1");
+    assert_eq!(rows[1]["job_size"], "2");
+    assert_eq!(rows[0]["proof_bytes"], PROOF_BYTES.to_string());
+    assert_eq!(rows[0]["threads"], "");
+    assert_eq!(rows[0]["machine"], "");
+    assert_eq!(rows[0]["repetition"], "0");
+    assert_eq!(rows[0]["discarded"], "0");
+
+    let json = read_json(&json_path(&out));
+    assert_eq!(json.len(), 2);
+    assert_eq!(json[1]["job_size"], 2);
+    assert_eq!(json[1]["output_bytes"], 4);
+    assert_eq!(json[1]["proof_details"]["len"], PROOF_BYTES);
+    assert_eq!(
+        json[1]["proof_digest"].as_str(),
+        Some(rows[1]["proof_digest"].as_str())
+    );
+}
+
+#[test]
+fn appends_to_existing_output_without_repeating_the_header() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+
+    run_jobs::<Mock>(
+        &out,
+        &vec![MockSpec::new(1)],
+        &provers(),
+        &RunOptions::default(),
+    );
+    run_jobs::<Mock>(
+        &out,
+        &vec![MockSpec::new(2)],
+        &provers(),
+        &RunOptions::default(),
+    );
+
+    let text = fs::read_to_string(&out).unwrap();
+    assert_eq!(text.lines().filter(|l| l.starts_with("prover,")).count(), 1);
+    let sizes: Vec<String> = read_csv(&out)
+        .into_iter()
+        .map(|row| row["job_size"].clone())
+        .collect();
+    assert_eq!(sizes, ["1", "2"]);
+    assert_eq!(read_json(&json_path(&out)).len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "write to a new file instead")]
+fn refuses_to_append_to_output_with_other_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    // The header of results written before durations were in milliseconds
+    let old =
+        "prover,job_name,job_size,proof_duration_microsec,verify_duration_microsec,proof_bytes\n\
+               mock,mock,1,2000,1000,16\n";
+    fs::write(&out, old).unwrap();
+
+    let spec = MockSpec::new(1);
+    let result = std::panic::catch_unwind(|| {
+        run_jobs::<Mock>(
+            &out,
+            &vec![spec.clone()],
+            &provers(),
+            &RunOptions::default(),
+        );
+    });
+
+    // Nothing was proved or written before giving up
+    assert_eq!(spec.calls(), 0);
+    assert_eq!(fs::read_to_string(&out).unwrap(), old);
+    std::panic::resume_unwind(result.unwrap_err());
+}
+
+#[test]
+fn resume_skips_jobs_already_in_the_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let first = MockSpec::new(1);
+    let options = RunOptions {
+        repetitions: Some(2),
+        threads: vec![1],
+        ..Default::default()
+    };
+    run_jobs::<Mock>(&out, &vec![first.clone()], &provers(), &options);
+    assert_eq!(first.calls(), 2);
+
+    // Picks up with the job size and thread count that did not run yet
+    let second = MockSpec::new(2);
+    let options = RunOptions {
+        threads: vec![1, 2],
+        resume: true,
+        ..options
+    };
+    let metrics = run_jobs::<Mock>(
+        &out,
+        &vec![first.clone(), second.clone()],
+        &provers(),
+        &options,
+    );
+
+    assert_eq!(first.calls(), 4);
+    assert_eq!(second.calls(), 4);
+    let runs: Vec<(u32, Option<usize>)> = metrics.iter().map(|m| (m.job_size, m.threads)).collect();
+    assert_eq!(
+        runs,
+        [
+            (1, Some(2)),
+            (1, Some(2)),
+            (2, Some(1)),
+            (2, Some(1)),
+            (2, Some(2)),
+            (2, Some(2))
+        ]
+    );
+    assert_eq!(read_csv(&out).len(), 8);
+}
+
+#[test]
+fn resume_completes_jobs_with_too_few_repetitions() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let spec = MockSpec::new(1);
+    run_jobs::<Mock>(
+        &out,
+        &vec![spec.clone()],
+        &provers(),
+        &RunOptions::default(),
+    );
+
+    let options = RunOptions {
+        repetitions: Some(2),
+        resume: true,
+        ..Default::default()
+    };
+    let metrics = run_jobs::<Mock>(&out, &vec![spec.clone()], &provers(), &options);
+
+    // Only the missing repetition runs, numbered after the one already there
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(metrics[0].repetition, 1);
+    assert_eq!(spec.calls(), 2);
+    let repetitions: Vec<String> = read_csv(&out)
+        .into_iter()
+        .map(|r| r["repetition"].clone())
+        .collect();
+    assert_eq!(repetitions, ["0", "1"]);
+}
+
+#[test]
+fn resume_reruns_jobs_recorded_at_another_security_level() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let spec = MockSpec::new(1);
+    run_jobs::<Mock>(
+        &out,
+        &vec![spec.clone()],
+        &provers(),
+        &RunOptions::default(),
+    );
+    // As if proved at another level under the same prover name
+    let data = fs::read_to_string(&out).unwrap();
+    let column = data
+        .lines()
+        .next()
+        .unwrap()
+        .split(',')
+        .position(|c| c == "security_bits")
+        .unwrap();
+    let data: Vec<String> = data
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let mut fields: Vec<&str> = line.split(',').collect();
+            if index > 0 {
+                fields[column] = "128";
+            }
+            fields.join(",")
+        })
+        .collect();
+    fs::write(&out, data.join("\n") + "\n").unwrap();
+
+    let options = RunOptions {
+        resume: true,
+        ..Default::default()
+    };
+    let metrics = run_jobs::<Mock>(&out, &vec![spec.clone()], &provers(), &options);
+
+    assert_eq!(metrics.len(), 1);
+    assert_eq!(spec.calls(), 2);
+}
+
+#[test]
+fn sweeps_repetitions_and_thread_counts() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let options = RunOptions {
+        repetitions: Some(2),
+        threads: vec![1, 2],
+        ..Default::default()
+    };
+
+    let metrics = run_jobs::<Mock>(&out, &vec![MockSpec::new(1)], &provers(), &options);
+
+    let runs: Vec<(Option<usize>, u32)> =
+        metrics.iter().map(|m| (m.threads, m.repetition)).collect();
+    assert_eq!(
+        runs,
+        [(Some(1), 0), (Some(1), 1), (Some(2), 0), (Some(2), 1)]
+    );
+    assert_eq!(read_csv(&out).len(), 4);
+}
+
+#[test]
+#[should_panic(expected = "Guest and host outputs differ")]
+fn fails_when_host_and_guest_outputs_differ() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = MockSpec {
+        mismatch: true,
+        ..MockSpec::new(1)
+    };
+    run_jobs::<Mock>(
+        &dir.path().join("out.csv"),
+        &vec![spec],
+        &provers(),
+        &RunOptions::default(),
+    );
+}
+
+#[test]
+#[should_panic(expected = "Proof failed verification")]
+fn fails_when_verification_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = MockSpec {
+        fail_verify: true,
+        ..MockSpec::new(1)
+    };
+    run_jobs::<Mock>(
+        &dir.path().join("out.csv"),
+        &vec![spec],
+        &provers(),
+        &RunOptions::default(),
+    );
+}
+
+#[test]
+fn reruns_outlier_repetitions() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    // The second run is much slower than the others
+    let spec = MockSpec::with_delays(1, &[10, 100, 10, 10, 10, 10, 10, 10]);
+    let options = RunOptions {
+        repetitions: Some(4),
+        ..Default::default()
+    };
+
+    let metrics = run_jobs::<Mock>(&out, &vec![spec.clone()], &provers(), &options);
+
+    assert_eq!(metrics.len(), 4);
+    assert!(metrics.iter().all(|m| m.discarded >= 1));
+    assert!(metrics.iter().all(|m| m.repetition != 1));
+    assert!(metrics
+        .iter()
+        .all(|m| m.proof_duration < Duration::from_millis(100)));
+    assert_eq!(spec.calls(), 4 + metrics[0].discarded as usize);
+    assert!(read_csv(&out).iter().all(|row| row["discarded"] != "0"));
+}
+
+#[test]
+fn keeps_outliers_without_reruns() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = MockSpec::with_delays(1, &[10, 100, 10, 10]);
+    let options = RunOptions {
+        repetitions: Some(4),
+        max_reruns: Some(0),
+        ..Default::default()
+    };
+
+    let metrics = run_jobs::<Mock>(
+        &dir.path().join("out.csv"),
+        &vec![spec.clone()],
+        &provers(),
+        &options,
+    );
+
+    assert_eq!(metrics.len(), 4);
+    assert!(metrics.iter().all(|m| m.discarded == 0));
+    assert_eq!(spec.calls(), 4);
+}
+
+#[test]
+fn determinism_check_proves_twice() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = MockSpec::new(1);
+    let options = RunOptions {
+        check: Some(Check::Determinism),
+        ..Default::default()
+    };
+
+    let metrics = run_jobs::<Mock>(
+        &dir.path().join("out.csv"),
+        &vec![spec.clone()],
+        &provers(),
+        &options,
+    );
+
+    assert_eq!(spec.calls(), 2);
+    assert_eq!(metrics[0].output_digest, metrics[1].output_digest);
+    assert_eq!(metrics[0].proof_digest, metrics[1].proof_digest);
+}
+
+#[test]
+#[should_panic(expected = "Nondeterministic output")]
+fn determinism_check_fails_on_differing_outputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = MockSpec {
+        nondeterministic: true,
+        ..MockSpec::new(1)
+    };
+    let options = RunOptions {
+        check: Some(Check::Determinism),
+        ..Default::default()
+    };
+    run_jobs::<Mock>(
+        &dir.path().join("out.csv"),
+        &vec![spec],
+        &provers(),
+        &options,
+    );
+}
+
+#[test]
+fn execute_check_compares_outputs_without_writing_results() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let specs = vec![MockSpec::new(1), MockSpec::new(2)];
+    let options = RunOptions {
+        check: Some(Check::Execute),
+        repetitions: Some(3),
+        ..Default::default()
+    };
+
+    let metrics = run_jobs::<Mock>(&out, &specs, &provers(), &options);
+
+    assert!(metrics.is_empty());
+    assert!(!out.exists());
+    assert!(specs.iter().all(|spec| spec.calls() == 1));
+}
+
+#[test]
+#[should_panic(expected = "Guest and host outputs differ")]
+fn execute_check_fails_on_differing_outputs() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = MockSpec {
+        mismatch: true,
+        ..MockSpec::new(1)
+    };
+    let options = RunOptions {
+        check: Some(Check::Execute),
+        ..Default::default()
+    };
+    run_jobs::<Mock>(
+        &dir.path().join("out.csv"),
+        &vec![spec],
+        &provers(),
+        &options,
+    );
+}
+
+#[test]
+fn confines_jobs_to_the_machine_profile() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let suite = dir.path().join("suite.toml");
+    fs::write(&suite, "[machines.one]\ncores = 1\n").unwrap();
+    let options = RunOptions {
+        suite: Some(suite),
+        machine: Some(String::from("one")),
+        ..Default::default()
+    };
+
+    let metrics = run_jobs::<Mock>(&out, &vec![MockSpec::new(1)], &provers(), &options);
+
+    assert_eq!(metrics[0].machine.as_deref(), Some("one"));
+    // Without explicit thread counts the pool matches the machine's cores
+    assert_eq!(metrics[0].threads, Some(1));
+    let rows = read_csv(&out);
+    assert_eq!(rows[0]["machine"], "one");
+    assert_eq!(rows[0]["threads"], "1");
+}
+
+#[test]
+fn verifies_saved_proofs() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.csv");
+    let options = RunOptions {
+        save_proofs: true,
+        ..Default::default()
+    };
+    run_jobs::<Mock>(
+        &out,
+        &vec![MockSpec::new(1), MockSpec::new(2)],
+        &provers(),
+        &options,
+    );
+    assert!(dir.path().join("proofs/mock/mock_1_mock.bin").exists());
+
+    let verify_out = dir.path().join("verify.csv");
+    let options = RunOptions {
+        repetitions: Some(2),
+        ..Default::default()
+    };
+    verify_jobs::<Mock>(&verify_out, &dir.path().join("proofs"), &options);
+
+    let rows = read_csv(&verify_out);
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0]["job_size"], "1");
+    assert_eq!(rows[3]["job_size"], "2");
+    assert_eq!(rows[3]["repetition"], "1");
+    assert_eq!(rows[0]["proof_bytes"], PROOF_BYTES.to_string());
+    assert_eq!(rows[0]["proof_digest"], read_csv(&out)[0]["proof_digest"]);
+}
+
+#[test]
+fn verify_ignores_missing_proofs() {
+    let dir = tempfile::tempdir().unwrap();
+    let verify_out = dir.path().join("verify.csv");
+
+    verify_jobs::<Mock>(
+        &verify_out,
+        &dir.path().join("proofs"),
+        &RunOptions::default(),
+    );
+
+    assert!(!verify_out.exists());
+}
+
+#[test]
+#[should_panic(expected = "failed verification")]
+fn verify_fails_on_bad_saved_proof() {
+    let dir = tempfile::tempdir().unwrap();
+    let spec = MockSpec {
+        corrupt_saved: true,
+        ..MockSpec::new(1)
+    };
+    let options = RunOptions {
+        save_proofs: true,
+        ..Default::default()
+    };
+    run_jobs::<Mock>(
+        &dir.path().join("out.csv"),
+        &vec![spec],
+        &provers(),
+        &options,
+    );
+
+    verify_jobs::<Mock>(
+        &dir.path().join("verify.csv"),
+        &dir.path().join("proofs"),
+        &RunOptions::default(),
+    );
+}